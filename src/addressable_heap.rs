@@ -0,0 +1,102 @@
+//! A common interface of the addressable heaps of this crate.
+//!
+//! Both `ptr_heap::PairingHeap` and `vec_heap::PairingHeap` implement `AddressableHeap`
+//! so that generic code can be written once and used with either representation.
+
+/// An addressable min-heap that returns a handle for every pushed element.
+///
+/// The handle of an element stays valid until the element is removed from the heap
+/// and can be used to query the element or to decrease its key.
+pub trait AddressableHeap<T, K> {
+    /// The handle that is associated with a pushed element.
+    type Handle: Copy;
+
+    /// The error that is returned by fallible operations such as `decrease_key`.
+    type Error;
+
+    /// Inserts the given element with its associated key and returns its handle.
+    fn push(&mut self, elem: T, key: K) -> Self::Handle;
+
+    /// Removes the element with the minimum key and returns it.
+    ///
+    /// Returns `None` if the heap is empty.
+    fn pop(&mut self) -> Option<T>;
+
+    /// Returns a reference to the element with the minimum key.
+    ///
+    /// Returns `None` if the heap is empty.
+    fn peek(&self) -> Option<&T>;
+
+    /// Decreases the key of the element associated with the given handle.
+    ///
+    /// Returns an error if the handle is not associated with an element
+    /// or if the new key is not lower than the previous key.
+    fn decrease_key(&mut self, handle: Self::Handle, new_key: K) -> Result<(), Self::Error>;
+
+    /// Returns a reference to the element associated with the given handle.
+    fn get(&self, handle: Self::Handle) -> Option<&T>;
+
+    /// Returns the number of elements stored in the heap.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the heap stores no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ptr_heap;
+    use vec_heap;
+
+    /// Computes the shortest distances from node `0` of the given adjacency lists
+    /// with Dijkstra's algorithm on top of any `AddressableHeap`.
+    fn dijkstra<H>(mut heap: H, edges: &[Vec<(usize, u32)>]) -> Vec<Option<u32>>
+    where
+        H: AddressableHeap<usize, u32>,
+    {
+        let mut dist = vec![None; edges.len()];
+        let mut handles = vec![None; edges.len()];
+        handles[0] = Some(heap.push(0, 0));
+        dist[0] = Some(0);
+        while let Some(node) = heap.pop() {
+            assert!(heap.len() < edges.len());
+            let base = dist[node].unwrap();
+            for &(next, weight) in &edges[node] {
+                let relaxed = base + weight;
+                match (dist[next], handles[next]) {
+                    (None, _) => {
+                        handles[next] = Some(heap.push(next, relaxed));
+                        dist[next] = Some(relaxed);
+                    }
+                    (Some(old), Some(handle)) if relaxed < old && heap.get(handle).is_some() => {
+                        assert!(heap.decrease_key(handle, relaxed).is_ok());
+                        assert_eq!(Some(&next), heap.get(handle));
+                        dist[next] = Some(relaxed);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        assert!(heap.is_empty());
+        assert_eq!(None, heap.peek());
+        dist
+    }
+
+    #[test]
+    fn generic_over_both_heaps() {
+        let edges = vec![
+            vec![(1, 7), (2, 2), (3, 9)],
+            vec![(4, 1)],
+            vec![(1, 3), (3, 4), (4, 8)],
+            vec![(4, 1)],
+            vec![],
+            vec![(0, 1)],
+        ];
+        let expected = vec![Some(0), Some(5), Some(2), Some(6), Some(6), None];
+        assert_eq!(expected, dijkstra(ptr_heap::PairingHeap::new(), &edges));
+        assert_eq!(expected, dijkstra(vec_heap::PairingHeap::new(), &edges));
+    }
+}
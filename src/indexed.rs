@@ -0,0 +1,206 @@
+//! The identifier-indexed variant of the addressable pairing heap shared by both representations.
+//!
+//! The variant only relies on methods that both `PairingHeap`s provide, so it is written once
+//! and expanded into the `indexed` module of either representation by `indexed_module!`.
+
+macro_rules! indexed_module {
+    () => {
+        /// An addressable pairing heap that is indexed by external identifiers.
+        ///
+        /// The `IndexedPairingHeap` wraps a `PairingHeap` together with a map from identifiers
+        /// to the handles of their elements so that callers never have to keep track of handles.
+        pub mod indexed {
+            use super::{Error, Handle, Key, PairingHeap, Result};
+            use std::collections::HashMap;
+            use std::hash::Hash;
+
+            /// An addressable pairing heap whose elements are accessed by user provided identifiers.
+            ///
+            /// Every identifier is associated with at most one element at a time.
+            /// The map from identifiers to handles is updated on every insertion and removal.
+            #[derive(Debug, Clone)]
+            pub struct IndexedPairingHeap<Id, T, K>
+            where
+                Id: Hash + Eq + Clone,
+                K: Key,
+            {
+                heap: PairingHeap<(Id, T), K>,
+                handles: HashMap<Id, Handle>,
+            }
+
+            impl<Id, T, K> Default for IndexedPairingHeap<Id, T, K>
+            where
+                Id: Hash + Eq + Clone,
+                K: Key,
+            {
+                fn default() -> Self {
+                    IndexedPairingHeap {
+                        heap: PairingHeap::default(),
+                        handles: HashMap::new(),
+                    }
+                }
+            }
+
+            impl<Id, T, K> IndexedPairingHeap<Id, T, K>
+            where
+                Id: Hash + Eq + Clone,
+                K: Key,
+            {
+                /// Creates a new instance of an `IndexedPairingHeap`.
+                #[inline]
+                pub fn new() -> Self {
+                    Self::default()
+                }
+
+                /// Returns the number of elements stored in this `IndexedPairingHeap`.
+                #[inline]
+                pub fn len(&self) -> usize {
+                    self.heap.len()
+                }
+
+                /// Returns true if this `IndexedPairingHeap` is empty.
+                #[inline]
+                pub fn is_empty(&self) -> bool {
+                    self.heap.is_empty()
+                }
+
+                /// Removes all elements from this `IndexedPairingHeap`.
+                #[inline]
+                pub fn clear(&mut self) {
+                    self.heap.clear();
+                    self.handles.clear();
+                }
+
+                /// Returns `true` if an element is associated with the given `id`.
+                #[inline]
+                pub fn contains(&self, id: &Id) -> bool {
+                    self.handles.contains_key(id)
+                }
+
+                /// Inserts a new element with the given key and associates it with the given `id`.
+                ///
+                /// If an element is already associated with `id` it is removed and returned.
+                pub fn push(&mut self, id: Id, elem: T, key: K) -> Option<T> {
+                    let replaced = self.remove(&id);
+                    let handle = self.heap.push((id.clone(), elem), key);
+                    self.handles.insert(id, handle);
+                    replaced
+                }
+
+                /// Decreases the key of the element associated with the given `id`.
+                ///
+                /// Returns an error if no element is associated with `id`
+                /// or if the new key is not lower than the current key.
+                #[inline]
+                pub fn decrease_key(&mut self, id: &Id, new_key: K) -> Result<()> {
+                    let handle = *self.handles.get(id).ok_or(Error::InvalidHandle)?;
+                    self.heap.decrease_key(handle, new_key)
+                }
+
+                /// Removes the element associated with the given `id` and returns it.
+                pub fn remove(&mut self, id: &Id) -> Option<T> {
+                    let handle = self.handles.remove(id)?;
+                    self.heap.remove(handle).map(|(_, elem)| elem)
+                }
+
+                /// Returns a reference to the element associated with the given `id`.
+                #[inline]
+                pub fn get(&self, id: &Id) -> Option<&T> {
+                    let handle = *self.handles.get(id)?;
+                    self.heap.get(handle).map(|(_, elem)| elem)
+                }
+
+                /// Returns the key of the element associated with the given `id`.
+                #[inline]
+                pub fn get_key(&self, id: &Id) -> Option<K> {
+                    let handle = *self.handles.get(id)?;
+                    self.heap.get_key(handle)
+                }
+
+                /// Returns the identifier and a reference to the current minimum element if not empty.
+                #[inline]
+                pub fn peek(&self) -> Option<(&Id, &T)> {
+                    self.heap.peek().map(|(id, elem)| (id, elem))
+                }
+
+                /// Returns the key of the current minimum element if not empty.
+                #[inline]
+                pub fn peek_key(&self) -> Option<K> {
+                    self.heap.peek_key()
+                }
+
+                /// Removes the element associated with the minimum key and returns it
+                /// together with its identifier.
+                pub fn pop(&mut self) -> Option<(Id, T)> {
+                    let (id, elem) = self.heap.pop()?;
+                    self.handles.remove(&id);
+                    Some((id, elem))
+                }
+            }
+
+            #[cfg(test)]
+            mod tests {
+                use super::*;
+
+                #[test]
+                fn push_pop() {
+                    let mut ph = IndexedPairingHeap::new();
+                    assert_eq!(None, ph.push("a", 'a', 3));
+                    assert_eq!(None, ph.push("b", 'b', 1));
+                    assert_eq!(None, ph.push("c", 'c', 2));
+                    assert_eq!(Some((&"b", &'b')), ph.peek());
+                    assert_eq!(Some('b'), ph.push("b", 'B', 4));
+                    assert_eq!(3, ph.len());
+                    assert_eq!(Some(&'B'), ph.get(&"b"));
+                    assert_eq!(Some(4), ph.get_key(&"b"));
+                    assert_eq!(Some(("c", 'c')), ph.pop());
+                    assert!(!ph.contains(&"c"));
+                    assert_eq!(Some('a'), ph.remove(&"a"));
+                    assert_eq!(None, ph.remove(&"a"));
+                    assert_eq!(Err(Error::InvalidHandle), ph.decrease_key(&"a", 0));
+                    assert_eq!(Some(("b", 'B')), ph.pop());
+                    assert_eq!(None, ph.pop());
+                    assert!(ph.is_empty());
+                }
+
+                #[test]
+                fn dijkstra() {
+                    let edges: Vec<Vec<(usize, u32)>> = vec![
+                        vec![(1, 7), (2, 2), (3, 9)],
+                        vec![(4, 1)],
+                        vec![(1, 3), (3, 4), (4, 8)],
+                        vec![(4, 1)],
+                        vec![],
+                        vec![(0, 1)],
+                    ];
+                    let mut dist = vec![None; edges.len()];
+                    let mut ph = IndexedPairingHeap::new();
+                    ph.push(0, (), 0);
+                    while let Some(key) = ph.peek_key() {
+                        let (node, ()) = ph.pop().unwrap();
+                        dist[node] = Some(key);
+                        for &(next, weight) in &edges[node] {
+                            if dist[next].is_some() {
+                                continue;
+                            }
+                            let relaxed = key + weight;
+                            match ph.get_key(&next) {
+                                None => {
+                                    ph.push(next, (), relaxed);
+                                }
+                                Some(old) if relaxed < old => {
+                                    assert_eq!(Ok(()), ph.decrease_key(&next, relaxed));
+                                }
+                                Some(_) => (),
+                            }
+                        }
+                    }
+                    assert_eq!(
+                        vec![Some(0), Some(5), Some(2), Some(6), Some(6), None],
+                        dist
+                    );
+                }
+            }
+        }
+    };
+}
@@ -12,16 +12,38 @@
 //! This implementation stores elements within a `Stash` that allocates elements
 //! densely within an array.
 //!
+//! Both heap representations implement the `AddressableHeap` trait so that generic code
+//! can be written once for either of them.
+//!
 //! It is possible to use custom types as the underlying `Key` type by implementing
-//! the `Key` trait.
+//! the `Key` trait. Floating point keys can be used via `OrderedF64` and `OrderedF32`.
 
 extern crate rand;
 #[cfg(all(feature = "bench", test))]
 extern crate test;
 
 extern crate itertools;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "smallvec")]
+extern crate smallvec;
 extern crate stash;
 extern crate unreachable;
 
+pub mod addressable_heap;
+#[macro_use]
+mod indexed;
+#[macro_use]
+mod max;
+mod nonmax;
+pub mod ordered_float;
 pub mod ptr_heap;
 pub mod vec_heap;
+
+pub use addressable_heap::AddressableHeap;
+pub use ordered_float::{OrderedF32, OrderedF64};
+
+#[cfg(all(test, feature = "serde"))]
+mod test_serde;
@@ -0,0 +1,201 @@
+//! The max-oriented variant of the addressable pairing heap shared by both representations.
+//!
+//! The variant only relies on methods that both `PairingHeap`s provide, so it is written once
+//! and expanded into the `max` module of either representation by `max_module!`.
+
+macro_rules! max_module {
+    () => {
+        /// A max-oriented variant of the addressable pairing heap.
+        ///
+        /// The `MaxPairingHeap` wraps a `PairingHeap` and stores its keys reversed
+        /// so that the element with the maximum key is accessed and removed first.
+        pub mod max {
+            use super::{Handle, Key, PairingHeap, Result};
+            use std::cmp::Reverse;
+
+            /// An addressable pairing heap that returns the element with the maximum key first.
+            #[derive(Debug, Clone)]
+            pub struct MaxPairingHeap<T, K>
+            where
+                K: Key,
+            {
+                heap: PairingHeap<T, Reverse<K>>,
+            }
+
+            impl<T, K> Default for MaxPairingHeap<T, K>
+            where
+                K: Key,
+            {
+                fn default() -> Self {
+                    MaxPairingHeap {
+                        heap: PairingHeap::default(),
+                    }
+                }
+            }
+
+            impl<T, K> MaxPairingHeap<T, K>
+            where
+                K: Key,
+            {
+                /// Creates a new instance of a `MaxPairingHeap`.
+                #[inline]
+                pub fn new() -> Self {
+                    Self::default()
+                }
+
+                /// Returns the number of elements stored in this `MaxPairingHeap`.
+                #[inline]
+                pub fn len(&self) -> usize {
+                    self.heap.len()
+                }
+
+                /// Returns true if this `MaxPairingHeap` is empty.
+                #[inline]
+                pub fn is_empty(&self) -> bool {
+                    self.heap.is_empty()
+                }
+
+                /// Removes all elements from this `MaxPairingHeap` while retaining its allocated capacity.
+                ///
+                /// All handles to elements of this `MaxPairingHeap` are invalidated.
+                #[inline]
+                pub fn clear(&mut self) {
+                    self.heap.clear()
+                }
+
+                /// Inserts a new element with the given key and returns a handle to it.
+                #[inline]
+                pub fn push(&mut self, elem: T, key: K) -> Handle {
+                    self.heap.push(elem, Reverse(key))
+                }
+
+                /// Increases the key of the element with the associated given `handle`
+                /// which raises its priority within this `MaxPairingHeap`.
+                ///
+                /// Returns an error if the new key is not greater than the current key.
+                #[inline]
+                pub fn increase_key(&mut self, handle: Handle, new_key: K) -> Result<()> {
+                    self.heap.decrease_key(handle, Reverse(new_key))
+                }
+
+                /// Changes the key of the element with the associated given `handle` to `new_key`.
+                #[inline]
+                pub fn change_key(&mut self, handle: Handle, new_key: K) -> Result<()> {
+                    self.heap.change_key(handle, Reverse(new_key))
+                }
+
+                /// Removes the element associated with the given `handle` and returns it.
+                #[inline]
+                pub fn remove(&mut self, handle: Handle) -> Option<T> {
+                    self.heap.remove(handle)
+                }
+
+                /// Returns `true` if the given handle is associated with an element of this `MaxPairingHeap`.
+                #[inline]
+                pub fn contains(&self, handle: Handle) -> bool {
+                    self.heap.contains(handle)
+                }
+
+                /// Returns a reference to the element associated with the given handle.
+                #[inline]
+                pub fn get(&self, handle: Handle) -> Option<&T> {
+                    self.heap.get(handle)
+                }
+
+                /// Returns a mutable reference to the element associated with the given handle.
+                #[inline]
+                pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+                    self.heap.get_mut(handle)
+                }
+
+                /// Returns the key of the element associated with the given handle.
+                #[inline]
+                pub fn get_key(&self, handle: Handle) -> Option<K> {
+                    self.heap.get_key(handle).map(|Reverse(key)| key)
+                }
+
+                /// Returns a reference to the current maximum element if not empty.
+                #[inline]
+                pub fn peek(&self) -> Option<&T> {
+                    self.heap.peek()
+                }
+
+                /// Returns the handle of the current maximum element if not empty.
+                #[inline]
+                pub fn peek_handle(&self) -> Option<Handle> {
+                    self.heap.peek_handle()
+                }
+
+                /// Returns the key of the current maximum element if not empty.
+                #[inline]
+                pub fn peek_key(&self) -> Option<K> {
+                    self.heap.peek_key().map(|Reverse(key)| key)
+                }
+
+                /// Removes the element associated with the maximum key and returns it.
+                #[inline]
+                pub fn pop(&mut self) -> Option<T> {
+                    self.heap.pop()
+                }
+
+                /// Consumes this `MaxPairingHeap` and returns a vector of its values in descending key order.
+                #[inline]
+                pub fn into_sorted_vec(self) -> Vec<T> {
+                    self.heap.into_sorted_vec()
+                }
+            }
+
+            #[cfg(test)]
+            mod tests {
+                use super::*;
+
+                #[test]
+                fn take_max() {
+                    let mut ph = MaxPairingHeap::new();
+                    ph.push(0, 6);
+                    ph.push(1, 10);
+                    ph.push(2, -42);
+                    ph.push(3, 1337);
+                    ph.push(4, -1);
+                    ph.push(5, 1);
+                    assert_eq!(Some(3), ph.pop());
+                    assert_eq!(Some(1), ph.pop());
+                    assert_eq!(Some(0), ph.pop());
+                    assert_eq!(Some(5), ph.pop());
+                    assert_eq!(Some(4), ph.pop());
+                    assert_eq!(Some(2), ph.pop());
+                    assert_eq!(None, ph.pop());
+                }
+
+                #[test]
+                fn increase_key() {
+                    let mut ph = MaxPairingHeap::new();
+                    let a = ph.push('a', 1);
+                    ph.push('b', 5);
+                    ph.push('c', 3);
+                    assert_eq!(Some(5), ph.peek_key());
+                    assert_eq!(Ok(()), ph.increase_key(a, 10));
+                    assert_eq!(Some(&'a'), ph.peek());
+                    assert_eq!(Some(10), ph.get_key(a));
+                    assert!(ph.increase_key(a, 0).is_err());
+                    assert!(ph.increase_key(a, 10).is_err());
+                    assert_eq!(Ok(()), ph.change_key(a, 0));
+                    assert_eq!(vec!['b', 'c', 'a'], ph.into_sorted_vec());
+                }
+
+                #[test]
+                fn remove() {
+                    let mut ph = MaxPairingHeap::new();
+                    ph.push('a', 1);
+                    let b = ph.push('b', 2);
+                    ph.push('c', 3);
+                    assert_eq!(Some('b'), ph.remove(b));
+                    assert!(!ph.contains(b));
+                    assert_eq!(Some('c'), ph.pop());
+                    assert_eq!(Some('a'), ph.pop());
+                    assert!(ph.is_empty());
+                }
+            }
+        }
+    };
+}
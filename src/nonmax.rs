@@ -0,0 +1,126 @@
+//! Index types that leave room for a niche so that optional indices need no extra space.
+
+use std::fmt;
+use std::num::{NonZeroU16, NonZeroU32, NonZeroUsize};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// An unsigned integer type that determines the width of the index stored in handles.
+///
+/// This is implemented for `usize`, `u32` and `u16`. Narrower index types shrink handles
+/// and thereby nodes but limit the number of elements a heap can store to
+/// the maximum value of the index type.
+///
+/// Once a heap has handed out all indices of its index type, `push` panics while
+/// `try_push` returns the rejected element and key.
+pub trait IndexType: Copy + Eq + fmt::Debug + private::Sealed {
+    /// The bitwise inverted index which is never zero.
+    #[doc(hidden)]
+    type NonZero: Copy + Eq;
+
+    /// Returns the bitwise inverted index or `None` if it does not fit below the maximum value.
+    #[doc(hidden)]
+    fn invert(value: usize) -> Option<Self::NonZero>;
+
+    /// Returns the index of the given bitwise inverted index.
+    #[doc(hidden)]
+    fn revert(inverted: Self::NonZero) -> usize;
+}
+
+macro_rules! index_type {
+    ($ty:ty, $non_zero:ty) => {
+        impl private::Sealed for $ty {}
+
+        impl IndexType for $ty {
+            type NonZero = $non_zero;
+
+            #[inline]
+            fn invert(value: usize) -> Option<Self::NonZero> {
+                if value >= <$ty>::MAX as usize {
+                    return None;
+                }
+                <$non_zero>::new(!(value as $ty))
+            }
+
+            #[inline]
+            fn revert(inverted: Self::NonZero) -> usize {
+                !inverted.get() as usize
+            }
+        }
+    };
+}
+
+index_type!(usize, NonZeroUsize);
+index_type!(u32, NonZeroU32);
+index_type!(u16, NonZeroU16);
+
+/// An index of the given `IndexType` that is never its maximum value.
+///
+/// The index is stored bitwise inverted so that `Option<NonMax<Ix>>`
+/// has the same size as `Ix`.
+pub(crate) struct NonMax<Ix: IndexType>(Ix::NonZero);
+
+impl<Ix: IndexType> NonMax<Ix> {
+    /// Returns the given value as `NonMax` or `None` if it is not below the maximum of `Ix`.
+    #[inline]
+    pub(crate) fn new(value: usize) -> Option<Self> {
+        Ix::invert(value).map(NonMax)
+    }
+
+    /// Returns the value as a primitive `usize`.
+    #[inline]
+    pub(crate) fn get(self) -> usize {
+        Ix::revert(self.0)
+    }
+}
+
+impl<Ix: IndexType> Copy for NonMax<Ix> {}
+
+impl<Ix: IndexType> Clone for NonMax<Ix> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Ix: IndexType> PartialEq for NonMax<Ix> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<Ix: IndexType> Eq for NonMax<Ix> {}
+
+impl<Ix: IndexType> fmt::Debug for NonMax<Ix> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.get(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn round_trip() {
+        for &value in &[0, 1, 42, usize::MAX - 1] {
+            assert_eq!(Some(value), NonMax::<usize>::new(value).map(NonMax::get));
+        }
+        assert_eq!(None, NonMax::<usize>::new(usize::MAX));
+        assert_eq!(Some(65_534), NonMax::<u16>::new(65_534).map(NonMax::get));
+        assert_eq!(None, NonMax::<u16>::new(65_535));
+        assert_eq!(None, NonMax::<u32>::new(usize::MAX));
+        assert_eq!("42", format!("{:?}", NonMax::<u32>::new(42).unwrap()));
+    }
+
+    #[test]
+    fn niche() {
+        assert_eq!(size_of::<usize>(), size_of::<Option<NonMax<usize>>>());
+        assert_eq!(size_of::<u32>(), size_of::<Option<NonMax<u32>>>());
+        assert_eq!(size_of::<u16>(), size_of::<Option<NonMax<u16>>>());
+    }
+}
@@ -0,0 +1,124 @@
+//! Totally ordered floating point wrappers that can be used as heap keys.
+//!
+//! The primitive floating point types are not `Ord` because of NaN.
+//! The wrappers in this module order NaN greater than every other value
+//! and equal to itself so that they satisfy the `Key` trait of both heaps.
+
+use std::cmp::Ordering;
+
+macro_rules! ordered_float {
+    ($name:ident, $float:ty, $doc:expr) => {
+        #[doc = $doc]
+        ///
+        /// NaN is considered greater than every other value and equal to itself.
+        /// Apart from that the natural ordering of floats applies so `-0.0` and `0.0` are equal.
+        #[derive(Debug, Copy, Clone, Default)]
+        pub struct $name(pub $float);
+
+        impl $name {
+            /// Returns the wrapped float.
+            #[inline]
+            pub fn into_inner(self) -> $float {
+                self.0
+            }
+        }
+
+        impl From<$float> for $name {
+            #[inline]
+            fn from(val: $float) -> Self {
+                $name(val)
+            }
+        }
+
+        impl From<$name> for $float {
+            #[inline]
+            fn from(val: $name) -> Self {
+                val.0
+            }
+        }
+
+        impl PartialEq for $name {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other) == Ordering::Equal
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialOrd for $name {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                match (self.0.is_nan(), other.0.is_nan()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) => self
+                        .0
+                        .partial_cmp(&other.0)
+                        .expect("non-NaN floats are always comparable"),
+                }
+            }
+        }
+    };
+}
+
+ordered_float!(
+    OrderedF64,
+    f64,
+    "A totally ordered `f64` that can be used as key of a `PairingHeap`."
+);
+ordered_float!(
+    OrderedF32,
+    f32,
+    "A totally ordered `f32` that can be used as key of a `PairingHeap`."
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{f32, f64};
+
+    #[test]
+    fn ordering() {
+        assert!(OrderedF64(1.0) < OrderedF64(2.0));
+        assert!(OrderedF64(f64::INFINITY) < OrderedF64(f64::NAN));
+        assert_eq!(OrderedF64(f64::NAN), OrderedF64(f64::NAN));
+        assert_eq!(OrderedF64(-0.0), OrderedF64(0.0));
+        assert!(OrderedF32(f32::NEG_INFINITY) < OrderedF32(-1.5));
+        assert!(OrderedF32(f32::MAX) < OrderedF32(f32::NAN));
+        assert_eq!(2.5f64, f64::from(OrderedF64::from(2.5)));
+    }
+
+    #[test]
+    fn ptr_heap_drain_order() {
+        use ptr_heap::PairingHeap;
+
+        let mut ph = PairingHeap::new();
+        for &(elem, key) in &[('n', f64::NAN), ('a', -3.5), ('c', 2.0), ('b', 0.25)] {
+            ph.push(elem, OrderedF64::from(key));
+        }
+        let inf = ph.push('i', OrderedF64(f64::INFINITY));
+        assert_eq!(Ok(()), ph.decrease_key(inf, OrderedF64(1.0)));
+        assert_eq!(vec!['a', 'b', 'i', 'c', 'n'], ph.into_sorted_vec());
+    }
+
+    #[test]
+    fn vec_heap_drain_order() {
+        use vec_heap::PairingHeap;
+
+        let mut ph = PairingHeap::new();
+        for &(elem, key) in &[('n', f32::NAN), ('a', -3.5), ('c', 2.0), ('b', 0.25)] {
+            ph.push(elem, OrderedF32::from(key));
+        }
+        let nan = ph.push('m', OrderedF32(f32::NAN));
+        assert_eq!(Ok(()), ph.decrease_key(nan, OrderedF32(1.0)));
+        assert_eq!(vec!['a', 'b', 'm', 'c', 'n'], ph.into_sorted_vec());
+    }
+}
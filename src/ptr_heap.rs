@@ -11,65 +11,130 @@
 //! It is possible to use custom types as the underlying `Key` type by implementing
 //! the `Key` trait.
 
+pub use nonmax::IndexType;
+use nonmax::NonMax;
 use stash::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+use std::sync::Arc;
 // use itertools::*;
 
+indexed_module!();
+max_module!();
+
 /// A handle to access stored elements within an addressable pairing heap.
+///
+/// With the `generational` feature enabled handles additionally carry the generation
+/// of their element so that handles of removed elements are rejected even if their
+/// slot got reused by another element.
+///
+/// The index of a handle is stored as the given `IndexType` and is never its maximum value
+/// so that `Option<Handle>` needs no extra space.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub struct Handle(usize);
+pub struct Handle<Ix: IndexType = usize> {
+    idx: NonMax<Ix>,
+    gen: Generation,
+}
 
-impl Handle {
+impl<Ix: IndexType> Handle<Ix> {
     #[inline]
-    fn uninitialized() -> Self {
-        Handle(usize::max_value())
+    fn with_generation(self, gen: Generation) -> Self {
+        Handle { idx: self.idx, gen }
     }
 }
 
-impl From<usize> for Handle {
-    fn from(val: usize) -> Handle {
-        Handle(val)
+impl<Ix: IndexType> From<usize> for Handle<Ix> {
+    fn from(val: usize) -> Handle<Ix> {
+        Handle {
+            idx: NonMax::new(val).expect("handle index out of range"),
+            gen: NO_GENERATION,
+        }
     }
 }
 
-impl From<Handle> for usize {
-    fn from(handle: Handle) -> usize {
-        handle.0
+impl<Ix: IndexType> From<Handle<Ix>> for usize {
+    fn from(handle: Handle<Ix>) -> usize {
+        handle.idx.get()
     }
 }
 
+/// The generation of an element which is unique for every inserted element.
+#[cfg(feature = "generational")]
+type Generation = u64;
+
+/// The generation of handles that are not associated with any element.
+#[cfg(feature = "generational")]
+const NO_GENERATION: Generation = 0;
+
+/// Generations are not tracked without the `generational` feature.
+#[cfg(not(feature = "generational"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Generation;
+
+/// The generation of handles that are not associated with any element.
+#[cfg(not(feature = "generational"))]
+const NO_GENERATION: Generation = Generation;
+
+/// Returns a new generation that has not been returned before.
+#[cfg(feature = "generational")]
+#[inline]
+fn next_generation() -> Generation {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static NEXT_GENERATION: AtomicU64 = AtomicU64::new(NO_GENERATION + 1);
+    NEXT_GENERATION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Returns a new generation that has not been returned before.
+#[cfg(not(feature = "generational"))]
+#[inline]
+fn next_generation() -> Generation {
+    Generation
+}
+
 /// Represents a trait for keys within an addressable pairing heap.
 ///
 /// A user can use custom type for the key type by implementing this trait.
 ///
 /// This trait is implicitely implemented already for all types that
-/// are `Copy`, `PartialOrd` and `Ord`.
-pub trait Key: Copy + PartialOrd + Ord {}
-impl<T> Key for T where T: Copy + PartialOrd + Ord {}
+/// are `Clone` and `Ord`.
+pub trait Key: Clone + Ord {}
+impl<T> Key for T where T: Clone + Ord {}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-struct Node<K>
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Node<T, K, Ix>
 where
     K: Key,
+    Ix: IndexType,
 {
-    parent: Option<Handle>,
-    child: Option<Handle>,
-    left: Handle,
-    right: Handle,
+    parent: Option<Handle<Ix>>,
+    child: Option<Handle<Ix>>,
+    left: Handle<Ix>,
+    right: Handle<Ix>,
     key: K,
+    elem: T,
+    seq: u64,
+    gen: Generation,
 }
 
-impl<K> Node<K>
+impl<T, K, Ix> Node<T, K, Ix>
 where
     K: Key,
+    Ix: IndexType,
 {
+    /// Creates a new node that is going to be stored at the given handle
+    /// and that forms a singleton ring.
     #[inline]
-    fn with_key(key: K) -> Self {
+    fn new(handle: Handle<Ix>, key: K, elem: T, seq: u64) -> Self {
         Node {
             parent: None,
             child: None,
-            left: Handle::uninitialized(),
-            right: Handle::uninitialized(),
+            left: handle,
+            right: handle,
             key,
+            elem,
+            seq,
+            gen: handle.gen,
         }
     }
 
@@ -87,14 +152,105 @@ where
 pub enum Error {
     /// Caused when using `decrease_key` method with a `new_key` that is greater than the old one.
     DecreaseKeyOutOfOrder,
+    /// Caused when using a handle that is not associated with an element of the `PairingHeap`.
+    InvalidHandle,
+    /// Caused when the update at the given index of `decrease_key_bulk` has a `new_key`
+    /// that is not lower than the current one.
+    DecreaseKeyOutOfOrderAt(usize),
+    /// Caused when the update at the given index of `decrease_key_bulk` uses a handle
+    /// that is not associated with an element of the `PairingHeap`.
+    InvalidHandleAt(usize),
+    /// Caused when using `compare_and_decrease` while the current key differs from the expected one.
+    KeyMismatch,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match *self {
+            Error::DecreaseKeyOutOfOrder => {
+                f.write_str("new key must be less than the current key for decrease_key")
+            }
+            Error::InvalidHandle => {
+                f.write_str("handle is not associated with an element of the heap")
+            }
+            Error::DecreaseKeyOutOfOrderAt(index) => write!(
+                f,
+                "new key of update {} must be less than the current key for decrease_key_bulk",
+                index
+            ),
+            Error::InvalidHandleAt(index) => write!(
+                f,
+                "handle of update {} is not associated with an element of the heap",
+                index
+            ),
+            Error::KeyMismatch => {
+                f.write_str("current key does not match the expected key for compare_and_decrease")
+            }
+        }
+    }
+}
+
+impl ::std::error::Error for Error {}
+
+/// Errors that describe why a handle is not associated with an element of a `PairingHeap`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HandleError {
+    /// Caused by a handle whose index has never been handed out by the `PairingHeap`.
+    OutOfRange,
+    /// Caused by a handle whose element has been removed from the `PairingHeap`.
+    Vacant,
+}
+
+impl Display for HandleError {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match *self {
+            HandleError::OutOfRange => {
+                f.write_str("handle index is beyond the storage of the heap")
+            }
+            HandleError::Vacant => f.write_str("handle refers to a removed element of the heap"),
+        }
+    }
 }
 
+impl ::std::error::Error for HandleError {}
+
 /// Generic `Result` type for `PairingHeap` methods.
 pub type Result<T> = ::std::result::Result<T, Error>;
 
+/// Strategy that is used to pair up the root trees of a `PairingHeap`.
+///
+/// Pairing takes place whenever the minimum element is removed or when
+/// the roots are consolidated explicitly.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum PairingStrategy {
+    /// Performs a single pass over the roots linking them pairwise
+    /// which halves the number of roots.
+    ///
+    /// This is the cheapest strategy per `pop` but it lazily leaves up to half
+    /// of the roots behind so that later operations have to deal with them.
+    #[default]
+    SinglePass,
+    /// Performs a pass linking the roots pairwise from left to right followed by
+    /// a pass linking the resulting trees from right to left into a single tree.
+    ///
+    /// This is the standard variant of pairing heaps with `O(log n)` amortized `pop`
+    /// and sub-logarithmic amortized `decrease_key`.
+    TwoPass,
+    /// Repeatedly performs pairing passes over the roots until only one root remains.
+    ///
+    /// This also leaves a single tree behind but its proven amortized bound for `pop`
+    /// of `O(log n log log n / log log log n)` is weaker than the one of `TwoPass`.
+    MultiPass,
+}
+
 /// Type alias for `PairingHeap` that has `i64` as default `Key` type.
 pub type DefaultPairingHeap<T> = PairingHeap<T, i64>;
 
+/// A shared comparator that orders the keys of a `PairingHeap`.
+///
+/// Clones of a `PairingHeap` share the comparator of the original heap.
+pub type Comparator<K> = Arc<dyn Fn(&K, &K) -> Ordering + Send + Sync>;
+
 /// An addressable pairing heap implementation.
 ///
 /// Stores elements with an associated key.
@@ -112,149 +268,224 @@ pub type DefaultPairingHeap<T> = PairingHeap<T, i64>;
 /// simply increases the priority of the associated element.
 ///
 /// It is possible to use different implementations for `Key` as the key type.
-#[derive(Debug, Clone)]
-pub struct PairingHeap<T, K>
+///
+/// A `PairingHeap` is `Send` and `Sync` whenever `T` and `K` are since it only consists of
+/// its storage, plain handles and a comparator that is itself `Send` and `Sync`.
+///
+/// The `IndexType` `Ix` determines the width of the indices stored in handles and nodes.
+/// It defaults to `usize` while `u32` or `u16` shrink the nodes for heaps that never
+/// store more elements than the index type can address. Heaps with a non-default
+/// index type are created via `Default` and configured with `set_strategy`, `set_stable`,
+/// `set_max_len` and `set_comparator` instead of the constructors that are only defined
+/// for `usize`.
+pub struct PairingHeap<T, K, Ix = usize>
 where
     K: Key,
+    Ix: IndexType,
 {
     /// Handle to the element with the minimum key within the pairing heap.
-    min: Option<Handle>,
+    min: Option<Handle<Ix>>,
 
     /// In the ```data``` vector all elements are stored.
     /// This indirection to the real data allows for efficient addressable elements via handles.
-    nodes: Stash<Node<K>, Handle>,
-    elems: Stash<T, Handle>,
+    nodes: Stash<Node<T, K, Ix>, Handle<Ix>>,
+
+    /// The strategy used to pair up roots.
+    strategy: PairingStrategy,
+
+    /// The comparator used to order the keys or `None` for their natural ordering.
+    cmp: Option<Comparator<K>>,
+
+    /// Whether elements with equal keys are ordered by their insertion order.
+    stable: bool,
+
+    /// The number of elements beyond which `try_push` refuses to insert.
+    max_len: Option<usize>,
+
+    /// The sequence number of the next inserted element.
+    seq: u64,
+
+    /// The number of slots of the element storage which is one past the highest index
+    /// that has been handed out since the storage was last cleared.
+    slots: usize,
 }
 
-struct RawHandleIter {
-    sentinel: Handle,
-    peek: Handle,
-    done: bool,
+struct RawHandleIter<Ix: IndexType> {
+    sentinel: Handle<Ix>,
+    peek: Option<Handle<Ix>>,
 }
 
-impl RawHandleIter {
-    fn children<T, K>(heap: &PairingHeap<T, K>, parent: Handle) -> RawHandleIter
+impl<Ix: IndexType> RawHandleIter<Ix> {
+    fn children<T, K>(heap: &PairingHeap<T, K, Ix>, parent: Handle<Ix>) -> RawHandleIter<Ix>
     where
         K: Key,
     {
         match heap.node(parent).child {
-            None => RawHandleIter::empty(),
+            None => RawHandleIter {
+                sentinel: parent,
+                peek: None,
+            },
             Some(child) => RawHandleIter::siblings(child),
         }
     }
 
-    fn siblings(handle: Handle) -> RawHandleIter {
+    fn siblings(handle: Handle<Ix>) -> RawHandleIter<Ix> {
         RawHandleIter {
             sentinel: handle,
-            peek: handle,
-            done: false,
+            peek: Some(handle),
         }
     }
 
-    fn empty() -> RawHandleIter {
-        RawHandleIter {
-            sentinel: Handle::uninitialized(),
-            peek: Handle::uninitialized(),
-            done: true,
-        }
-    }
-
-    fn next<T, K>(&mut self, heap: &PairingHeap<T, K>) -> Option<Handle>
+    fn next<T, K>(&mut self, heap: &PairingHeap<T, K, Ix>) -> Option<Handle<Ix>>
     where
         K: Key,
     {
-        if self.done {
-            return None;
-        }
-
-        let next = self.peek;
-        self.peek = heap.node(next).right;
-        if self.peek == self.sentinel {
-            self.done = true;
-        }
+        let next = self.peek?;
+        let right = heap.node(next).right;
+        self.peek = if right == self.sentinel {
+            None
+        } else {
+            Some(right)
+        };
         Some(next)
     }
 }
 
-struct HandleIter<'a, T, K>
+struct HandleIter<'a, T, K, Ix>
 where
     K: Key + 'a,
     T: 'a,
+    Ix: IndexType + 'a,
 {
-    heap: &'a PairingHeap<T, K>,
-    iter: RawHandleIter, // sentinel: Handle,
-                         // peek    : Handle,
-                         // done    : bool
+    heap: &'a PairingHeap<T, K, Ix>,
+    iter: RawHandleIter<Ix>,
 }
 
-impl<'a, T, K> HandleIter<'a, T, K>
+impl<'a, T, K, Ix> HandleIter<'a, T, K, Ix>
 where
     K: Key + 'a,
     T: 'a,
+    Ix: IndexType,
 {
     /// Iterator over the children of the given parent node.
-    fn children(heap: &'a PairingHeap<T, K>, parent: Handle) -> HandleIter<'a, T, K> {
+    fn children(heap: &'a PairingHeap<T, K, Ix>, parent: Handle<Ix>) -> HandleIter<'a, T, K, Ix> {
         HandleIter {
             heap,
             iter: RawHandleIter::children(heap, parent),
         }
-        // match heap.node(parent).child {
-        // 	None => HandleIter{
-        // 		heap: heap,
-        // 		iter: RawHandleIter::empty()
-        // 		// sentinel: Handle::uninitialized(),
-        // 		// peek    : Handle::uninitialized(),
-        // 		// done    : true
-        // 	},
-        // 	Some(child) => HandleIter::siblings(heap, child)
-        // }
     }
 
     /// Iterator over the siblings of the given child node.
     ///
     /// This also iterates inclusively over the given child.
-    fn siblings(heap: &'a PairingHeap<T, K>, child: Handle) -> HandleIter<'a, T, K> {
+    fn siblings(heap: &'a PairingHeap<T, K, Ix>, child: Handle<Ix>) -> HandleIter<'a, T, K, Ix> {
         HandleIter {
             heap,
-            iter: RawHandleIter::siblings(child), // sentinel: child,
-                                                  // peek    : child,
-                                                  // done    : false
+            iter: RawHandleIter::siblings(child),
         }
     }
 }
 
-impl<'a, T, K> Iterator for HandleIter<'a, T, K>
+impl<'a, T, K, Ix> Iterator for HandleIter<'a, T, K, Ix>
 where
     K: Key + 'a,
     T: 'a,
+    Ix: IndexType,
 {
-    type Item = Handle;
+    type Item = Handle<Ix>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.iter.next(self.heap)
-        // match self.done {
-        // 	true  => None,
-        // 	false => {
-        // 		let next = self.peek;
-        // 		self.peek = self.heap.node(next).right;
-        // 		if self.peek == self.sentinel {
-        // 			self.done = true;
-        // 		}
-        // 		Some(next)
-        // 	}
-        // }
     }
 }
 
-impl<T, K> Default for PairingHeap<T, K>
+/// A candidate of `iter_k_smallest` that is ordered reversely like the nodes of its heap
+/// so that the `BinaryHeap` of candidates yields the candidate that is popped first.
+struct Candidate<'a, T: 'a, K: 'a + Key, Ix: 'a + IndexType> {
+    node: &'a Node<T, K, Ix>,
+    handle: Handle<Ix>,
+    heap: &'a PairingHeap<T, K, Ix>,
+}
+
+impl<'a, T, K: Key, Ix: IndexType> PartialEq for Candidate<'a, T, K, Ix> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<'a, T, K: Key, Ix: IndexType> Eq for Candidate<'a, T, K, Ix> {}
+
+impl<'a, T, K: Key, Ix: IndexType> PartialOrd for Candidate<'a, T, K, Ix> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a, T, K: Key, Ix: IndexType> Ord for Candidate<'a, T, K, Ix> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.heap.cmp_nodes(other.node, self.node)
+    }
+}
+
+impl<T, K, Ix> Clone for PairingHeap<T, K, Ix>
+where
+    T: Clone,
+    K: Key,
+    Ix: IndexType,
+{
+    fn clone(&self) -> Self {
+        PairingHeap {
+            min: self.min,
+            nodes: self.nodes.clone(),
+            strategy: self.strategy,
+            cmp: self.cmp.clone(),
+            stable: self.stable,
+            max_len: self.max_len,
+            seq: self.seq,
+            slots: self.slots,
+        }
+    }
+
+    /// Clones `source` into `self` reusing the allocation of the nodes.
+    ///
+    /// Falls back to `clone` if `source` has vacant slots since only a plain clone
+    /// reproduces the order in which they are refilled and thereby the handles of new elements.
+    fn clone_from(&mut self, source: &Self) {
+        if source.len() < source.slots {
+            *self = source.clone();
+            return;
+        }
+        self.min = source.min;
+        self.nodes.clear();
+        self.nodes.reserve(source.len());
+        for (handle, node) in source.nodes.iter() {
+            let put = self.nodes.put(node.clone());
+            debug_assert_eq!(usize::from(handle), usize::from(put));
+        }
+        self.strategy = source.strategy;
+        self.cmp.clone_from(&source.cmp);
+        self.stable = source.stable;
+        self.max_len = source.max_len;
+        self.seq = source.seq;
+        self.slots = source.slots;
+    }
+}
+
+impl<T, K, Ix> Default for PairingHeap<T, K, Ix>
 where
     K: Key,
+    Ix: IndexType,
 {
     fn default() -> Self {
         Self {
             min: None,
             nodes: Stash::default(),
-            elems: Stash::default(),
+            strategy: PairingStrategy::default(),
+            cmp: None,
+            stable: false,
+            max_len: None,
+            seq: 0,
+            slots: 0,
         }
     }
 }
@@ -269,10 +500,236 @@ where
         Self::default()
     }
 
+    /// Creates a new `PairingHeap` from the given elements and their associated keys.
+    ///
+    /// All elements are inserted as roots without comparing their keys
+    /// and are then linked pairwise in a single pass by `heapify`.
+    /// So the new heap has at most half as many roots as one built by repeated `push`
+    /// which moves work from the first `pop` into the construction.
+    pub fn from_vec(items: Vec<(T, K)>) -> Self {
+        let mut heap = Self::default();
+        heap.reserve(items.len());
+        for (elem, key) in items {
+            let handle = heap.make_entry(key, elem);
+            match heap.min {
+                None => heap.insert_root(handle),
+                Some(min) => heap.add_sibling(min, handle),
+            }
+        }
+        heap.heapify();
+        heap
+    }
+
+    /// Creates a new `PairingHeap` from clones of the given elements and their associated keys.
+    ///
+    /// This builds the heap like `from_vec` without consuming the given items.
+    pub fn from_slice(items: &[(T, K)]) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_vec(items.to_vec())
+    }
+
+    /// Creates a new `PairingHeap` from the given elements and their associated keys
+    /// like `from_vec` but builds the heap on the threads of the global `rayon` thread pool.
+    ///
+    /// The elements are split into one chunk per thread of the pool whose sub-heaps are
+    /// built in parallel and melded into a single heap afterwards.
+    #[cfg(feature = "rayon")]
+    pub fn par_from_vec(items: Vec<(T, K)>) -> Self
+    where
+        T: Send,
+        K: Send,
+    {
+        Self::par_from_vec_in(items, ::rayon::current_num_threads())
+    }
+
+    /// Creates a new `PairingHeap` from the given elements split into `threads` chunks.
+    ///
+    /// Falls back to `from_vec` on the current thread if there are too few elements
+    /// to be worth splitting.
+    #[cfg(feature = "rayon")]
+    fn par_from_vec_in(mut items: Vec<(T, K)>, threads: usize) -> Self
+    where
+        T: Send,
+        K: Send,
+    {
+        use rayon::prelude::*;
+
+        if threads <= 1 || items.len() < 2 * threads {
+            return Self::from_vec(items);
+        }
+        let chunk_len = items.len().div_ceil(threads);
+        let mut chunks = Vec::with_capacity(threads);
+        while items.len() > chunk_len {
+            let chunk = items.split_off(items.len() - chunk_len);
+            chunks.push(chunk);
+        }
+        chunks.push(items);
+        let heaps = chunks
+            .into_par_iter()
+            .map(Self::from_vec)
+            .collect::<Vec<_>>();
+        Self::meld_all(heaps)
+    }
+
+    /// Creates a new instance of a stable `PairingHeap`.
+    ///
+    /// A stable `PairingHeap` breaks ties between equal keys by insertion order so that
+    /// elements with equal keys are popped in the order in which they were pushed.
+    pub fn new_stable() -> Self {
+        let mut heap = Self::default();
+        heap.set_stable(true);
+        heap
+    }
+
+    /// Creates a new instance of a `PairingHeap` that pairs up its roots
+    /// with the given `PairingStrategy`.
+    ///
+    /// See `PairingStrategy` for the tradeoffs of the different strategies.
+    pub fn with_strategy(strategy: PairingStrategy) -> Self {
+        PairingHeap {
+            strategy,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a new instance of a `PairingHeap` that stores at most `max_len` elements
+    /// when inserting with `try_push`.
+    ///
+    /// The storage for `max_len` elements is allocated up front so that `try_push`
+    /// never reallocates. Note that `push` does not respect the limit.
+    pub fn with_max_len(max_len: usize) -> Self {
+        let mut heap = Self::default();
+        heap.set_max_len(Some(max_len));
+        heap
+    }
+
+    /// Creates a new instance of a `PairingHeap` that orders its keys with
+    /// the given comparator instead of their natural ordering.
+    ///
+    /// The comparator must define a total order on the keys. For example
+    /// `|lhs, rhs| rhs.cmp(lhs)` turns this `PairingHeap` into a max-heap.
+    /// The comparator may capture its environment, e.g. a table of priorities.
+    pub fn with_comparator<C>(cmp: C) -> Self
+    where
+        C: Fn(&K, &K) -> Ordering + Send + Sync + 'static,
+    {
+        let mut heap = Self::default();
+        heap.set_comparator(cmp);
+        heap
+    }
+}
+
+impl<T, K, Ix> PairingHeap<T, K, Ix>
+where
+    K: Key,
+    Ix: IndexType,
+{
+    /// Returns `true` if elements with equal keys are popped in insertion order.
+    #[inline]
+    pub fn is_stable(&self) -> bool {
+        self.stable
+    }
+
+    /// Returns the number of elements beyond which `try_push` refuses to insert
+    /// or `None` if this `PairingHeap` is unbounded.
+    #[inline]
+    pub fn max_len(&self) -> Option<usize> {
+        self.max_len
+    }
+
+    /// Sets whether elements with equal keys are popped in insertion order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `PairingHeap` is not empty.
+    pub fn set_stable(&mut self, stable: bool) {
+        assert!(
+            self.is_empty(),
+            "cannot change the stability of a non-empty heap"
+        );
+        self.stable = stable;
+    }
+
+    /// Sets the number of elements beyond which `try_push` refuses to insert.
+    ///
+    /// The storage for `max_len` elements is allocated up front like for `with_max_len`.
+    /// Elements that are already stored beyond the new limit are kept.
+    pub fn set_max_len(&mut self, max_len: Option<usize>) {
+        self.max_len = max_len;
+        if let Some(max_len) = max_len {
+            let additional = max_len.saturating_sub(self.len());
+            self.reserve_exact(additional);
+        }
+    }
+
+    /// Sets the comparator that orders the keys instead of their natural ordering.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this `PairingHeap` is not empty.
+    pub fn set_comparator<C>(&mut self, cmp: C)
+    where
+        C: Fn(&K, &K) -> Ordering + Send + Sync + 'static,
+    {
+        assert!(
+            self.is_empty(),
+            "cannot change the comparator of a non-empty heap"
+        );
+        self.cmp = Some(Arc::new(cmp));
+    }
+
+    /// Creates a new and empty `PairingHeap` with the same strategy and comparator as this one.
+    fn empty_like(&self) -> Self {
+        PairingHeap {
+            strategy: self.strategy,
+            cmp: self.cmp.clone(),
+            stable: self.stable,
+            max_len: self.max_len,
+            ..Self::default()
+        }
+    }
+
+    /// Compares the given keys with the comparator of this `PairingHeap`.
+    #[inline]
+    fn cmp_keys(&self, lhs: &K, rhs: &K) -> Ordering {
+        match self.cmp {
+            Some(ref cmp) => cmp(lhs, rhs),
+            None => lhs.cmp(rhs),
+        }
+    }
+
+    /// Compares the keys of the given nodes and breaks ties by insertion order
+    /// if this `PairingHeap` is stable.
+    #[inline]
+    fn cmp_nodes(&self, lhs: &Node<T, K, Ix>, rhs: &Node<T, K, Ix>) -> Ordering {
+        let ordering = self.cmp_keys(&lhs.key, &rhs.key);
+        if self.stable {
+            ordering.then(lhs.seq.cmp(&rhs.seq))
+        } else {
+            ordering
+        }
+    }
+
+    /// Returns the next sequence number used to order elements by insertion.
+    #[inline]
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.seq;
+        self.seq += 1;
+        seq
+    }
+
+    /// Returns the number of elements this `PairingHeap` is able to store without reallocating.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.nodes.capacity()
+    }
+
     /// Returns the number of elements stored in this `PairingHeap`.
     #[inline]
     pub fn len(&self) -> usize {
-        self.elems.len()
+        self.nodes.len()
     }
 
     /// Returns true if this `PairingHeap` is empty.
@@ -281,31 +738,107 @@ where
         self.len() == 0
     }
 
+    /// Reserves capacity for at least `additional` more elements to be inserted
+    /// into this `PairingHeap`. May reserve more space to avoid frequent reallocations.
+    pub fn reserve(&mut self, additional: usize) {
+        self.nodes.reserve(additional);
+    }
+
+    /// Reserves capacity for exactly `additional` more elements to be inserted
+    /// into this `PairingHeap`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.nodes.reserve_exact(additional);
+    }
+
+    /// Shrinks the capacity of this `PairingHeap` as much as possible.
+    ///
+    /// This compacts the internal storage down to `len()` elements and thereby
+    /// renumbers the elements: all handles are invalidated and must be
+    /// translated with the returned `Remapping`.
+    ///
+    /// Since every node owns its element there is no separate element storage,
+    /// so compacting the nodes also moves the elements.
+    pub fn shrink_to_fit(&mut self) -> Remapping<Ix> {
+        let mut compacted = self.empty_like();
+        compacted.reserve_exact(self.len());
+        let old = ::std::mem::replace(self, compacted);
+        let handles = self.absorb(old);
+        Remapping {
+            absorbed_self: true,
+            handles,
+        }
+    }
+
+    /// Returns an estimate of the number of bytes held by the internal storage of this `PairingHeap`.
+    ///
+    /// This is the capacity of the node storage weighted by the size of a node
+    /// and does not account for memory owned by the elements or keys themselves.
+    pub fn memory_usage(&self) -> usize {
+        self.nodes.capacity() * ::std::mem::size_of::<Node<T, K, Ix>>()
+    }
+
+    /// Shrinks the capacity of the node storage of this `PairingHeap` as much as possible.
+    ///
+    /// Every node owns its element, so the element storage cannot be left untouched:
+    /// compacting the nodes moves the elements along with them. This is therefore the same
+    /// as `shrink_to_fit` and all handles must be translated with the returned `Remapping`.
+    #[inline]
+    pub fn shrink_nodes_to_fit(&mut self) -> Remapping<Ix> {
+        self.shrink_to_fit()
+    }
+
+    /// Swaps this `PairingHeap` with `other` in `O(1)`.
+    ///
+    /// Handles stay valid for their elements which now belong to the respective other heap.
+    /// The configuration of both heaps, such as their `PairingStrategy` and comparator,
+    /// moves along with the elements so that both heaps keep a consistent order.
+    pub fn swap(&mut self, other: &mut PairingHeap<T, K, Ix>) {
+        ::std::mem::swap(self, other);
+    }
+
+    /// Removes all elements from this `PairingHeap` while retaining its allocated capacity.
+    ///
+    /// All handles to elements of this `PairingHeap` are invalidated.
+    pub fn clear(&mut self) {
+        self.min = None;
+        self.nodes.clear();
+        self.slots = 0;
+        self.seq = 0;
+    }
+
+    /// Removes all elements from this `PairingHeap` and releases its allocated capacity.
+    ///
+    /// The configuration of this `PairingHeap`, such as its `PairingStrategy`, is kept.
+    /// All handles to elements of this `PairingHeap` are invalidated.
+    pub fn clear_and_free(&mut self) {
+        *self = self.empty_like();
+    }
+
     /// Returns a reference to the `Node` that is associated with the given handle.
     /// Note that this won't fail on usage for a correct implementation of `PairingHeap`.
     #[inline]
-    fn node(&self, handle: Handle) -> &Node<K> {
+    fn node(&self, handle: Handle<Ix>) -> &Node<T, K, Ix> {
         unsafe { self.nodes.get_unchecked(handle) }
     }
 
     /// Returns a mutable reference to the `Node` that is associated with the given handle.
     /// Note that this won't fail on usage for a correct implementation of `PairingHeap`.
     #[inline]
-    fn node_mut(&mut self, handle: Handle) -> &mut Node<K> {
+    fn node_mut(&mut self, handle: Handle<Ix>) -> &mut Node<T, K, Ix> {
         unsafe { self.nodes.get_unchecked_mut(handle) }
     }
 
-    fn raw_children(&self, parent: Handle) -> RawHandleIter {
+    fn raw_children(&self, parent: Handle<Ix>) -> RawHandleIter<Ix> {
         RawHandleIter::children(self, parent)
     }
 
-    fn raw_siblings(&self, sibling: Handle) -> RawHandleIter {
+    fn raw_siblings(&self, sibling: Handle<Ix>) -> RawHandleIter<Ix> {
         RawHandleIter::siblings(sibling)
     }
 
     /// Returns an iterator over all children of the given parent node.
     #[inline]
-    fn children(&self, parent: Handle) -> HandleIter<'_, T, K> {
+    fn children(&self, parent: Handle<Ix>) -> HandleIter<'_, T, K, Ix> {
         HandleIter::children(self, parent)
     }
 
@@ -313,13 +846,13 @@ where
     ///
     /// This also iterates inclusively over the given child.
     #[inline]
-    fn siblings(&self, child: Handle) -> HandleIter<'_, T, K> {
+    fn siblings(&self, child: Handle<Ix>) -> HandleIter<'_, T, K, Ix> {
         HandleIter::siblings(self, child)
     }
 
     /// Adds the given new child to the given child's siblings.
     #[inline]
-    fn add_sibling(&mut self, child: Handle, new_child: Handle) {
+    fn add_sibling(&mut self, child: Handle<Ix>, new_child: Handle<Ix>) {
         self.detach_siblings(new_child); // experimental!
         self.node_mut(new_child).parent = self.node(child).parent;
         self.node_mut(new_child).right = self.node(child).right;
@@ -329,9 +862,22 @@ where
         self.node_mut(rightright).left = new_child;
     }
 
+    /// Splices the siblings of `other` into the siblings of `child` right after `child`.
+    ///
+    /// Both nodes must be part of distinct sibling rings.
+    #[inline]
+    fn splice_siblings(&mut self, child: Handle<Ix>, other: Handle<Ix>) {
+        let right = self.node(child).right;
+        let other_left = self.node(other).left;
+        self.node_mut(child).right = other;
+        self.node_mut(other).left = child;
+        self.node_mut(other_left).right = right;
+        self.node_mut(right).left = other_left;
+    }
+
     /// Adds the given child to the parent node.
     #[inline]
-    fn add_child(&mut self, parent: Handle, new_child: Handle) {
+    fn add_child(&mut self, parent: Handle<Ix>, new_child: Handle<Ix>) {
         self.detach_siblings(new_child); // experimental!
         match self.node(parent).child {
             None => {
@@ -346,7 +892,7 @@ where
 
     /// Links the given `lower` tree under the given `upper` tree thus making `lower`
     /// a children of `upper`.
-    fn link(&mut self, upper: Handle, lower: Handle) {
+    fn link(&mut self, upper: Handle<Ix>, lower: Handle<Ix>) {
         debug_assert!(upper != lower, "cannot link to self!");
         debug_assert!(
             self.node(lower).is_root(),
@@ -354,20 +900,28 @@ where
         );
 
         self.add_child(upper, lower);
-        self.update_min(upper);
+        if self.min == Some(lower) {
+            self.min = Some(upper);
+        } else {
+            self.update_min(upper);
+        }
     }
 
     /// Links the element with the lower key over the element with the higher key.
     /// Thus making one the child of the other.
-    fn union(&mut self, fst: Handle, snd: Handle) {
+    ///
+    /// Returns the handle of the element that remains a root.
+    fn union(&mut self, fst: Handle<Ix>, snd: Handle<Ix>) -> Handle<Ix> {
         debug_assert!(self.node(fst).is_root());
         debug_assert!(self.node(snd).is_root());
         debug_assert!(fst != snd, "cannot union self with itself");
 
-        if self.node(fst).key < self.node(snd).key {
-            self.link(fst, snd)
+        if self.cmp_nodes(self.node(fst), self.node(snd)) == Ordering::Less {
+            self.link(fst, snd);
+            fst
         } else {
-            self.link(snd, fst)
+            self.link(snd, fst);
+            snd
         }
     }
 
@@ -375,28 +929,88 @@ where
     /// effectively decreases the number of roots to half.
     fn pairwise_union(&mut self) {
         if let Some(min) = self.min {
-            let mut siblings = self.siblings(min).collect::<Vec<_>>().into_iter();
-            loop {
-                match (siblings.next(), siblings.next()) {
-                    (Some(left), Some(right)) => self.union(left, right),
-                    (Some(left), None) => self.update_min(left),
-                    _ => break,
+            let mut roots = 0;
+            let mut raw_siblings = self.raw_siblings(min);
+            while raw_siblings.next(self).is_some() {
+                roots += 1;
+            }
+            // Linking may remove the first root from the ring so walk it by count.
+            let mut next = min;
+            for _ in 0..roots / 2 {
+                let left = next;
+                let right = self.node(left).right;
+                next = self.node(right).right;
+                self.union(left, right);
+            }
+            if roots % 2 == 1 {
+                self.update_min(next);
+            }
+        }
+    }
+
+    /// Pairwise unifies roots in the `PairingHeap` and then links the
+    /// resulting trees from right to left into a single tree.
+    fn two_pass_union(&mut self) {
+        self.pairwise_union();
+        if let Some(min) = self.min {
+            let mut roots = self.siblings(min).collect::<Vec<_>>().into_iter().rev();
+            if let Some(mut acc) = roots.next() {
+                for root in roots {
+                    acc = self.union(root, acc);
+                }
+            }
+        }
+    }
+
+    /// Links the roots of this `PairingHeap` pairwise in a single pass which halves their number.
+    ///
+    /// In contrast to `consolidate` this ignores the `PairingStrategy` of this `PairingHeap`.
+    pub fn heapify(&mut self) {
+        self.pairwise_union()
+    }
+
+    /// Pairs up the roots of this `PairingHeap` according to its `PairingStrategy`.
+    ///
+    /// This happens implicitly whenever the minimum element is removed
+    /// but may also be triggered explicitly in order to reduce the number of roots.
+    pub fn consolidate(&mut self) {
+        match self.strategy {
+            PairingStrategy::SinglePass => self.pairwise_union(),
+            PairingStrategy::TwoPass => self.two_pass_union(),
+            PairingStrategy::MultiPass => {
+                while let Some(min) = self.min {
+                    if self.node(min).right == min {
+                        break;
+                    }
+                    self.pairwise_union()
                 }
             }
-            // let mut raw_siblings = self.raw_siblings(min);
-            // loop {
-            // 	match (raw_siblings.next(self), raw_siblings.next(self)) {
-            // 		(Some(left), Some(right)) => self.union(left, right),
-            // 		(Some(left), None       ) => self.update_min(left),
-            // 		_                         => break
-            // 	}
-            // }
         }
     }
 
+    /// Returns the `PairingStrategy` that is used to pair up roots.
+    #[inline]
+    pub fn strategy(&self) -> PairingStrategy {
+        self.strategy
+    }
+
+    /// Returns the comparator that is used to order the keys
+    /// or `None` if the keys are ordered by their natural ordering.
+    #[inline]
+    pub fn comparator(&self) -> Option<&Comparator<K>> {
+        self.cmp.as_ref()
+    }
+
+    /// Sets the `PairingStrategy` that is used to pair up roots and
+    /// consolidates the roots with it so that the change takes effect right away.
+    pub fn set_strategy(&mut self, strategy: PairingStrategy) {
+        self.strategy = strategy;
+        self.consolidate();
+    }
+
     /// Adds the given handle as a new root node into the heap.
     #[inline]
-    fn insert_root(&mut self, new_root: Handle) {
+    fn insert_root(&mut self, new_root: Handle<Ix>) {
         // self.detach_siblings(new_root); // experimental!
         match self.min {
             None => {
@@ -414,68 +1028,249 @@ where
     /// Updates the internal pointer to the current minimum element by hinting
     /// to a new possible min element within the heap.
     #[inline]
-    fn update_min(&mut self, new: Handle) {
+    fn update_min(&mut self, new: Handle<Ix>) {
         match self.min {
             None => {
                 self.min = Some(new);
             }
             Some(min) => {
-                if self.node(new).key < self.node(min).key {
+                if self.cmp_nodes(self.node(new), self.node(min)) == Ordering::Less {
                     self.min = Some(new);
                 }
             }
         }
     }
 
-    /// Creates a new root node.
-    #[inline]
-    fn make_entry(&mut self, key: K, elem: T) -> Handle {
-        let node_handle = self.nodes.put(Node::with_key(key));
-        self.node_mut(node_handle).left = node_handle;
-        self.node_mut(node_handle).right = node_handle;
-        let elem_handle = self.elems.put(elem);
-        debug_assert_eq!(node_handle, elem_handle);
-        node_handle
+    /// Searches all roots for the element with the minimum key.
+    fn find_min(&mut self) {
+        if let Some(min) = self.min {
+            let mut roots = self.raw_siblings(min);
+            while let Some(root) = roots.next(self) {
+                self.update_min(root);
+            }
+        }
     }
 
-    /// Inserts the given element into the `PairingHeap` with its associated key
-    /// and returns a `Handle` to it that allows to directly address it.
+    /// Checks the invariants of this `PairingHeap` and describes the first violation found.
     ///
-    /// The handle is for example required in order to use methods like `decrease_key`.
+    /// The checked invariants are that all links between nodes and the handle to the minimum
+    /// element are live, that the sibling rings are consistent and reach every element,
+    /// that no child has a lower key than its parent and that the minimum element
+    /// is a root with the lowest key.
+    ///
+    /// This takes `O(n)` and is meant for debugging.
+    pub fn check_consistency(&self) -> ::std::result::Result<(), String> {
+        if let Some((handle, _)) = self
+            .nodes
+            .iter()
+            .find(|&(handle, _)| usize::from(handle) >= self.slots)
+        {
+            return Err(format!(
+                "{:?} lies beyond the {} tracked slots",
+                handle, self.slots
+            ));
+        }
+        for (handle, node) in self.nodes.iter() {
+            let links = [node.parent, node.child, Some(node.left), Some(node.right)];
+            for link in links.iter().filter_map(|&link| link) {
+                if self.lookup(link).is_none() {
+                    return Err(format!("dangling link from {:?} to {:?}", handle, link));
+                }
+            }
+        }
+        let idx = |handle: Option<Handle<Ix>>| handle.map(usize::from);
+        for (handle, node) in self.nodes.iter() {
+            if Some(usize::from(handle)) != idx(Some(self.node(node.left).right)) {
+                return Err(format!("broken sibling ring at {:?}", handle));
+            }
+            if idx(node.parent) != idx(self.node(node.right).parent) {
+                return Err(format!("siblings of {:?} differ in parent", handle));
+            }
+            if let Some(parent) = node.parent {
+                if self.cmp_keys(&node.key, &self.node(parent).key) == Ordering::Less {
+                    return Err(format!(
+                        "{:?} has a lower key than its parent {:?}",
+                        handle, parent
+                    ));
+                }
+            }
+        }
+        let min = match self.min {
+            Some(min) => min,
+            None if self.is_empty() => return Ok(()),
+            None => return Err("missing min of non-empty heap".to_owned()),
+        };
+        if self.lookup(min).is_none() {
+            return Err(format!("dangling min {:?}", min));
+        }
+        if !self.node(min).is_root() {
+            return Err(format!("min {:?} is not a root", min));
+        }
+        let mut reached = 0;
+        let mut stack = vec![min];
+        while let Some(first) = stack.pop() {
+            let mut siblings = self.raw_siblings(first);
+            while let Some(sibling) = siblings.next(self) {
+                reached += 1;
+                if reached > self.len() {
+                    return Err("sibling rings contain a cycle".to_owned());
+                }
+                if self.node(sibling).is_root()
+                    && self.cmp_nodes(self.node(sibling), self.node(min)) == Ordering::Less
+                {
+                    return Err(format!("root {:?} is lower than min", sibling));
+                }
+                stack.extend(self.node(sibling).child);
+            }
+        }
+        if reached != self.len() {
+            return Err("not all elements are reachable".to_owned());
+        }
+        Ok(())
+    }
+
+    /// Asserts that the invariants of this `PairingHeap` hold as checked by `check_consistency`.
+    ///
+    /// This is only available for tests or with the `paranoid` feature enabled.
+    #[cfg(any(test, feature = "paranoid"))]
+    pub fn assert_consistent(&self) {
+        if let Err(violation) = self.check_consistency() {
+            panic!("{}", violation);
+        }
+    }
+
+    /// Records that the slot of the given handle belongs to the element storage.
     #[inline]
-    pub fn push(&mut self, elem: T, key: K) -> Handle {
-        let handle = self.make_entry(key, elem);
-        self.insert_root(handle);
-        handle
+    fn occupy_slot(&mut self, handle: Handle<Ix>) {
+        self.slots = ::std::cmp::max(self.slots, usize::from(handle) + 1);
     }
 
-    /// Detaches the given child from its siblings.
+    /// Creates a new root node.
     #[inline]
-    fn detach_siblings(&mut self, child: Handle) {
+    fn make_entry(&mut self, key: K, elem: T) -> Handle<Ix> {
+        let seq = self.next_seq();
+        let handle = self.nodes.next_index().with_generation(next_generation());
+        let put = self.nodes.put(Node::new(handle, key, elem, seq));
+        debug_assert_eq!(usize::from(handle), usize::from(put));
+        self.occupy_slot(handle);
+        handle
+    }
+
+    /// Returns a reference to the `Node` associated with the given handle if the handle is valid.
+    #[inline]
+    fn lookup(&self, handle: Handle<Ix>) -> Option<&Node<T, K, Ix>> {
+        self.nodes.get(handle).filter(|node| node.gen == handle.gen)
+    }
+
+    /// Inserts the given element into the `PairingHeap` with its associated key
+    /// and returns a `Handle` to it that allows to directly address it.
+    ///
+    /// The handle is for example required in order to use methods like `decrease_key`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `IndexType` of this `PairingHeap` cannot address another element.
+    /// Use `try_push` in order to get the element back instead.
+    #[inline]
+    pub fn push(&mut self, elem: T, key: K) -> Handle<Ix> {
+        let handle = self.make_entry(key, elem);
+        self.insert_root(handle);
+        handle
+    }
+
+    /// Inserts the given element with its associated key unless this `PairingHeap`
+    /// already holds as many elements as its `max_len` allows.
+    ///
+    /// Returns the handle of the inserted element or the rejected element and key.
+    /// Heaps without a `max_len` accept the element as long as their `IndexType`
+    /// can address another element.
+    pub fn try_push(&mut self, elem: T, key: K) -> ::std::result::Result<Handle<Ix>, (T, K)> {
+        match self.max_len {
+            Some(max_len) if self.len() >= max_len => Err((elem, key)),
+            _ if !self.has_free_index() => Err((elem, key)),
+            _ => Ok(self.push(elem, key)),
+        }
+    }
+
+    /// Returns `true` if the `IndexType` can address the slot of the next inserted element.
+    ///
+    /// Vacant slots below `slots` are refilled first, otherwise the next slot is `slots` itself.
+    #[inline]
+    fn has_free_index(&self) -> bool {
+        self.len() < self.slots || NonMax::<Ix>::new(self.slots).is_some()
+    }
+
+    /// Inserts the given element with its associated key and afterwards removes and returns
+    /// the element with the greatest key if this `PairingHeap` holds more than `k` elements.
+    ///
+    /// Pushing all elements of a stream through this method retains the `k` elements with
+    /// the smallest keys. Since finding the greatest key requires a scan over all elements
+    /// this runs in `O(n)` whenever an element is discarded.
+    /// A stable `PairingHeap` discards the most recently inserted of several elements
+    /// with the greatest key, just like it would pop them last.
+    pub fn push_bounded(&mut self, elem: T, key: K, k: usize) -> Option<T> {
+        self.push(elem, key);
+        if self.len() <= k {
+            return None;
+        }
+        let max = self
+            .nodes
+            .iter()
+            .max_by(|lhs, rhs| self.cmp_nodes(lhs.1, rhs.1))
+            .map(|(index, node)| index.with_generation(node.gen))?;
+        self.remove(max)
+    }
+
+    /// Detaches the given child from its siblings leaving it behind as a singleton ring.
+    #[inline]
+    fn detach_siblings(&mut self, child: Handle<Ix>) {
         let right = self.node(child).right;
         let left = self.node(child).left;
 
         self.node_mut(right).left = left;
         self.node_mut(left).right = right;
 
-        // self.node_mut(child).left  = child;
-        // self.node_mut(child).right = child;
+        self.node_mut(child).left = child;
+        self.node_mut(child).right = child;
     }
 
     /// Cuts the given `child` from its parent and inserts it as a root into the `PairingHeap`.
     /// Will panic if the given `child` is not a child and thus a root node already.
     #[inline]
-    fn cut(&mut self, child: Handle) {
+    fn cut(&mut self, child: Handle<Ix>) {
         debug_assert!(self.node(child).is_child());
 
+        if let Some(parent) = self.node(child).parent {
+            if self.node(parent).child == Some(child) {
+                let right = self.node(child).right;
+                self.node_mut(parent).child = if right == child { None } else { Some(right) };
+            }
+        }
         self.detach_siblings(child);
         self.insert_root(child);
     }
 
     /// Decreases the key of the element with the associated given `handle`.
-    /// Will panic if the given new key is not lower than the previous key.
-    pub fn decrease_key(&mut self, handle: Handle, new_key: K) -> Result<()> {
-        if new_key >= self.node(handle).key {
+    ///
+    /// Returns an error if the given `handle` is not associated with an element
+    /// or if the given new key is not lower than the previous key.
+    pub fn decrease_key(&mut self, handle: Handle<Ix>, new_key: K) -> Result<()> {
+        if !self.contains(handle) {
+            return Err(Error::InvalidHandle);
+        }
+        unsafe { self.decrease_key_unchecked(handle, new_key) }
+    }
+
+    /// Decreases the key of the element with the associated given `handle`.
+    ///
+    /// Returns an error if the given new key is not lower than the previous key.
+    ///
+    /// # Safety
+    ///
+    /// This is unsafe because the handle is not checked to be associated with an element
+    /// of this heap.
+    pub unsafe fn decrease_key_unchecked(&mut self, handle: Handle<Ix>, new_key: K) -> Result<()> {
+        if self.cmp_keys(&new_key, &self.node(handle).key) != Ordering::Less {
             return Err(Error::DecreaseKeyOutOfOrder);
         }
 
@@ -488,25 +1283,269 @@ where
         Ok(())
     }
 
+    /// Decreases the keys of many elements at once by applying the given `(handle, new_key)`
+    /// updates in order.
+    ///
+    /// Stops at the first update that fails and returns an error with its index
+    /// while all preceding updates stay applied.
+    pub fn decrease_key_bulk(&mut self, updates: &[(Handle<Ix>, K)]) -> Result<()> {
+        for (index, (handle, new_key)) in updates.iter().enumerate() {
+            self.decrease_key(*handle, new_key.clone())
+                .map_err(|err| match err {
+                    Error::InvalidHandle => Error::InvalidHandleAt(index),
+                    _ => Error::DecreaseKeyOutOfOrderAt(index),
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Decreases the key of the element with the associated given `handle`
+    /// unless the given new key is equal to the previous key.
+    ///
+    /// Unlike `decrease_key` an equal key is accepted and leaves the heap unchanged.
+    ///
+    /// Returns an error if the given `handle` is not associated with an element
+    /// or if the given new key is greater than the previous key.
+    pub fn decrease_key_or_equal(&mut self, handle: Handle<Ix>, new_key: K) -> Result<()> {
+        if !self.contains(handle) {
+            return Err(Error::InvalidHandle);
+        }
+        if self.cmp_keys(&new_key, &self.node(handle).key) == Ordering::Equal {
+            return Ok(());
+        }
+        unsafe { self.decrease_key_unchecked(handle, new_key) }
+    }
+
+    /// Decreases the key of the element with the associated given `handle` to `new_key`
+    /// unless `new_key` is not lower than the current key in which case the key is kept.
+    ///
+    /// Unlike `decrease_key` this never fails because of the relation between the keys
+    /// and never raises the key which suits keys that are computed arithmetically.
+    ///
+    /// Returns an error if the given `handle` is not associated with an element.
+    pub fn decrease_key_saturating(&mut self, handle: Handle<Ix>, new_key: K) -> Result<()> {
+        if !self.contains(handle) {
+            return Err(Error::InvalidHandle);
+        }
+        if self.cmp_keys(&new_key, &self.node(handle).key) != Ordering::Less {
+            return Ok(());
+        }
+        unsafe { self.decrease_key_unchecked(handle, new_key) }
+    }
+
+    /// Decreases the key of the element with the associated given `handle` by `delta`
+    /// so that its new key is the previous key minus `delta`.
+    ///
+    /// Returns an error if the given `handle` is not associated with an element
+    /// or if the resulting key is not lower than the previous key, e.g. for a zero `delta`.
+    pub fn decrease_key_by(&mut self, handle: Handle<Ix>, delta: K) -> Result<()>
+    where
+        K: ::std::ops::Sub<Output = K>,
+    {
+        let old_key = self.get_key(handle).ok_or(Error::InvalidHandle)?;
+        self.decrease_key(handle, old_key - delta)
+    }
+
+    /// Decreases the key of the element with the associated given `handle`
+    /// only if its current key equals `expected_old`.
+    ///
+    /// This prevents a key that was computed from a stale view of the element
+    /// from overwriting a newer key.
+    ///
+    /// Returns an error if the given `handle` is not associated with an element,
+    /// if the current key differs from `expected_old` in which case the heap is left unchanged
+    /// or if the given new key is not lower than the current key.
+    pub fn compare_and_decrease(
+        &mut self,
+        handle: Handle<Ix>,
+        expected_old: K,
+        new_key: K,
+    ) -> Result<()> {
+        if !self.contains(handle) {
+            return Err(Error::InvalidHandle);
+        }
+        if self.cmp_keys(&self.node(handle).key, &expected_old) != Ordering::Equal {
+            return Err(Error::KeyMismatch);
+        }
+        self.decrease_key(handle, new_key)
+    }
+
+    /// Increases the key of the element with the associated given `handle`.
+    ///
+    /// Releases all children of the element as new roots since their keys
+    /// might now be lower than the increased key.
+    fn increase_key(&mut self, handle: Handle<Ix>, new_key: K) {
+        debug_assert_eq!(
+            Ordering::Greater,
+            self.cmp_keys(&new_key, &self.node(handle).key)
+        );
+
+        let was_min = self.min == Some(handle);
+        self.node_mut(handle).key = new_key;
+        self.release_children(handle);
+        if was_min {
+            self.find_min();
+        }
+    }
+
+    /// Changes the key of the element with the associated given `handle` to `new_key`.
+    ///
+    /// Decreases or increases the key depending on the relation between
+    /// the new and the old key and does nothing if they are equal.
+    ///
+    /// Returns an error if the given `handle` is not associated with an element.
+    pub fn change_key(&mut self, handle: Handle<Ix>, new_key: K) -> Result<()> {
+        if !self.contains(handle) {
+            return Err(Error::InvalidHandle);
+        }
+        let ordering = self.cmp_keys(&new_key, &self.node(handle).key);
+        match ordering {
+            Ordering::Less => unsafe { self.decrease_key_unchecked(handle, new_key) },
+            Ordering::Greater => {
+                self.increase_key(handle, new_key);
+                Ok(())
+            }
+            Ordering::Equal => Ok(()),
+        }
+    }
+
+    /// Sets the key of the element with the associated given `handle` to `new_key`.
+    ///
+    /// In contrast to `decrease_key` this never fails because of the relation between
+    /// the new and the old key: the element is moved up or down in the heap as needed.
+    /// The stored key is replaced even if it compares equal to the new key.
+    ///
+    /// Returns an error if the given `handle` is not associated with an element.
+    pub fn set_key(&mut self, handle: Handle<Ix>, new_key: K) -> Result<()> {
+        if !self.contains(handle) {
+            return Err(Error::InvalidHandle);
+        }
+        let ordering = self.cmp_keys(&new_key, &self.node(handle).key);
+        match ordering {
+            Ordering::Less => unsafe { self.decrease_key_unchecked(handle, new_key) },
+            Ordering::Greater => {
+                self.increase_key(handle, new_key);
+                Ok(())
+            }
+            Ordering::Equal => {
+                self.node_mut(handle).key = new_key;
+                Ok(())
+            }
+        }
+    }
+
+    /// Mutates the element and the key associated with the given `handle` through `f`
+    /// and restores the heap order afterwards.
+    ///
+    /// The element is moved up if its key decreased and its children are released
+    /// as new roots if its key increased.
+    ///
+    /// Returns an error if the given `handle` is not associated with an element.
+    pub fn modify<F>(&mut self, handle: Handle<Ix>, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut T, &mut K),
+    {
+        let mut key = self.get_key(handle).ok_or(Error::InvalidHandle)?;
+        f(&mut self.node_mut(handle).elem, &mut key);
+        self.set_key(handle, key)
+    }
+
+    /// Updates the element associated with the given handle if there is one
+    /// and inserts the element with its key as a new element otherwise.
+    ///
+    /// An updated element gets its value replaced by `elem` and its key changed
+    /// to `key` regardless whether the key increases or decreases.
+    /// Returns the handle of the updated or inserted element.
+    pub fn update_or_push(&mut self, maybe: Option<Handle<Ix>>, elem: T, key: K) -> Handle<Ix> {
+        match maybe.filter(|&handle| self.contains(handle)) {
+            Some(handle) => {
+                self[handle] = elem;
+                self.change_key(handle, key)
+                    .expect("handle is associated with an element");
+                handle
+            }
+            None => self.push(elem, key),
+        }
+    }
+
     /// Release children from the given parent making them root nodes.
-    fn release_children(&mut self, parent: Handle) {
-        let mut raw_children = self.raw_children(parent);
-        while let Some(child) = raw_children.next(self) {
-            self.insert_root(child)
-        }
-        // for child in self.children(parent).collect::<Vec<_>>() {
-        // 	self.insert_root(child)
-        // }
+    fn release_children(&mut self, parent: Handle<Ix>) {
+        if let Some(first) = self.node(parent).child {
+            let anchor = self.min;
+            let mut raw_children = self.raw_children(parent);
+            while let Some(child) = raw_children.next(self) {
+                self.node_mut(child).parent = None;
+                self.update_min(child);
+            }
+            if let Some(anchor) = anchor {
+                self.splice_siblings(anchor, first);
+            }
+        }
         self.node_mut(parent).child = None;
     }
 
+    /// Returns a clone of the current minimum element if not empty.
+    #[inline]
+    pub fn peek_cloned(&self) -> Option<T>
+    where
+        T: Clone,
+    {
+        self.peek().cloned()
+    }
+
+    /// Returns the key of the current minimum element if not empty.
+    #[inline]
+    pub fn peek_key(&self) -> Option<K> {
+        self.peek_handle().and_then(|min| self.get_key(min))
+    }
+
+    /// Returns a copy of the key of the current minimum element if not empty.
+    ///
+    /// Equivalent to `peek_key`.
+    #[inline]
+    pub fn peek_key_cloned(&self) -> Option<K> {
+        self.peek_key()
+    }
+
+    /// Returns a reference to the current minimum element together with its key if not empty.
+    ///
+    /// Equivalent to combining `peek` and `peek_key` but looks up the minimum only once.
+    #[inline]
+    pub fn peek_with_key(&self) -> Option<(&T, K)> {
+        self.peek_handle().map(|min| {
+            let node = self.node(min);
+            (&node.elem, node.key.clone())
+        })
+    }
+
     /// Removes the element associated with the minimum key within this `PairingHeap` and returns it.
     #[inline]
     pub fn pop(&mut self) -> Option<T> {
-        match self.min {
-            Some(_) => Some(unsafe { self.pop_unchecked() }),
-            None => None,
+        self.min.map(|_| unsafe { self.pop_unchecked() })
+    }
+
+    /// Removes the element associated with the minimum key within this `PairingHeap`
+    /// and returns it together with the handle it was associated with.
+    ///
+    /// The returned handle is no longer valid for this heap but can be used to
+    /// clean up external bookkeeping of handles.
+    pub fn pop_handle(&mut self) -> Option<(Handle<Ix>, T)> {
+        let min = self.peek_handle()?;
+        self.pop().map(|elem| (min, elem))
+    }
+
+    /// Removes the `n` elements with the smallest keys and returns them in ascending key order.
+    ///
+    /// Returns fewer than `n` elements if this `PairingHeap` stores fewer elements.
+    pub fn pop_n(&mut self, n: usize) -> Vec<T> {
+        let mut popped = Vec::with_capacity(n.min(self.len()));
+        while popped.len() < n {
+            match self.pop() {
+                Some(elem) => popped.push(elem),
+                None => break,
+            }
         }
+        popped
     }
 
     /// Removes the element associated with the minimum key within this `PairingHeap` without
@@ -527,26 +1566,341 @@ where
                 if right != min {
                     self.min = Some(right);
                     self.detach_siblings(min);
-                    self.pairwise_union();
+                    self.consolidate();
                 } else {
                     self.min = None;
                 }
-                self.nodes.take_unchecked(min);
-                self.elems.take_unchecked(min)
+                self.nodes.take_unchecked(min).elem
+            }
+        }
+    }
+
+    /// Removes the element associated with the given `handle` from this `PairingHeap` and returns it.
+    ///
+    /// Returns `None` if there is no element associated with the given `handle`.
+    pub fn remove(&mut self, handle: Handle<Ix>) -> Option<T> {
+        self.lookup(handle)?;
+        if self.min == Some(handle) {
+            return self.pop();
+        }
+        if self.node(handle).is_child() {
+            self.cut(handle);
+        }
+        self.release_children(handle);
+        self.detach_siblings(handle);
+        self.nodes.take(handle).map(|node| node.elem)
+    }
+
+    /// Melds the given `other` heap into this `PairingHeap`.
+    ///
+    /// The elements of the smaller of both heaps are moved into the storage of the larger one
+    /// which keeps the amount of moved elements and reallocations low. Handles of the heap
+    /// that got absorbed are invalidated and can be translated via the returned `Remapping`.
+    ///
+    /// Note that if this heap is the smaller one it is this heap's handles that get remapped
+    /// while the handles of `other` stay valid. `Remapping::absorbed_self` tells which side
+    /// was absorbed.
+    ///
+    /// The configuration of this heap, such as its strategy, stability and maximum length,
+    /// is kept in either case. Both heaps are expected to order their keys with the same comparator.
+    pub fn meld(&mut self, mut other: PairingHeap<T, K, Ix>) -> Remapping<Ix> {
+        let absorbed_self = self.len() < other.len();
+        if absorbed_self {
+            ::std::mem::swap(self, &mut other);
+            ::std::mem::swap(&mut self.strategy, &mut other.strategy);
+            ::std::mem::swap(&mut self.cmp, &mut other.cmp);
+            ::std::mem::swap(&mut self.stable, &mut other.stable);
+            ::std::mem::swap(&mut self.max_len, &mut other.max_len);
+        }
+        let handles = self.absorb(other);
+        Remapping {
+            absorbed_self,
+            handles,
+        }
+    }
+
+    /// Melds all given heaps into a single `PairingHeap`.
+    ///
+    /// The elements of all heaps are moved into the storage of the largest one whose strategy
+    /// and comparator are kept. All roots are collected first and then paired up once
+    /// according to the strategy instead of pairing after every meld.
+    ///
+    /// Handles of the largest heap stay valid while the handles of all other heaps
+    /// are invalidated. Returns an empty heap if no heaps are given.
+    pub fn meld_all(mut heaps: Vec<PairingHeap<T, K, Ix>>) -> PairingHeap<T, K, Ix> {
+        let largest = (0..heaps.len()).max_by_key(|&idx| heaps[idx].len());
+        let mut melded = match largest {
+            Some(idx) => heaps.swap_remove(idx),
+            None => return PairingHeap::default(),
+        };
+        for heap in heaps {
+            melded.absorb(heap);
+        }
+        melded.consolidate();
+        melded
+    }
+
+    /// Moves all elements of `other` into this `PairingHeap`, leaving `other` empty.
+    ///
+    /// Unlike `meld` the `other` heap stays usable afterwards. All handles of `other`
+    /// are invalidated.
+    pub fn append(&mut self, other: &mut PairingHeap<T, K, Ix>) {
+        let emptied = other.empty_like();
+        let other = ::std::mem::replace(other, emptied);
+        self.absorb(other);
+    }
+
+    /// Melds the given `other` heap into this `PairingHeap` while dropping all elements
+    /// of `other` that are considered the `same` as an element that is already present.
+    ///
+    /// Elements of `other` are checked against all elements present at the time of
+    /// their insertion, so this runs in `O(m * (n + m))` for `n` elements in this heap
+    /// and `m` elements in `other`. Handles of `other` are invalidated.
+    pub fn meld_dedup<F>(&mut self, other: PairingHeap<T, K, Ix>, same: F)
+    where
+        F: Fn(&T, &T) -> bool,
+    {
+        for (elem, key) in other.into_pairs() {
+            if !self.values().any(|present| same(present, &elem)) {
+                self.push(elem, key);
+            }
+        }
+    }
+
+    /// Moves all elements of `other` into this `PairingHeap` and returns
+    /// the new handles of the moved elements indexed by their old handles.
+    fn absorb(&mut self, other: PairingHeap<T, K, Ix>) -> Vec<Option<Handle<Ix>>> {
+        let PairingHeap {
+            min: other_min,
+            nodes: other_nodes,
+            seq: other_seq,
+            ..
+        } = other;
+        self.seq = ::std::cmp::max(self.seq, other_seq);
+        self.nodes.reserve(other_nodes.len());
+        let mut handles = Vec::new();
+        let mut moved = Vec::with_capacity(other_nodes.len());
+        for (old, node) in other_nodes.into_iter() {
+            let gen = node.gen;
+            let new = self.nodes.put(node).with_generation(gen);
+            self.occupy_slot(new);
+            let idx = usize::from(old);
+            if handles.len() <= idx {
+                handles.resize(idx + 1, None);
+            }
+            handles[idx] = Some(new);
+            moved.push(new);
+        }
+        let remap =
+            |handle: Handle<Ix>| handles[usize::from(handle)].expect("missing remapped handle");
+        for new in moved {
+            let node = self.node_mut(new);
+            node.parent = node.parent.map(remap);
+            node.child = node.child.map(remap);
+            node.left = remap(node.left);
+            node.right = remap(node.right);
+        }
+        if let Some(other_min) = other_min.map(remap) {
+            match self.min {
+                None => self.min = Some(other_min),
+                Some(min) => {
+                    self.splice_siblings(min, other_min);
+                    self.update_min(other_min);
+                }
+            }
+        }
+        handles
+    }
+
+    /// Retains only the elements for which `f` returns `true` and removes all others.
+    ///
+    /// The `PairingHeap` is rebuilt from the retained elements which invalidates all handles.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T, &K) -> bool,
+    {
+        let retained = self.empty_like();
+        let old = ::std::mem::replace(self, retained);
+        self.extend(old.into_pairs().filter(|(elem, key)| f(elem, key)));
+    }
+
+    /// Splits this `PairingHeap` into `k` heaps of roughly equal size.
+    ///
+    /// The elements are dealt out round-robin so the resulting heaps are
+    /// balanced by their number of elements but not by their keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    pub fn split_into(self, k: usize) -> Vec<PairingHeap<T, K, Ix>> {
+        assert!(k != 0, "cannot split a heap into zero heaps");
+        let mut heaps = (0..k).map(|_| self.empty_like()).collect::<Vec<_>>();
+        for (n, (elem, key)) in self.into_pairs().enumerate() {
+            heaps[n % k].push(elem, key);
+        }
+        heaps
+    }
+
+    /// Removes all elements with a key that is not lower than `threshold` with respect to the
+    /// comparator from this `PairingHeap` and returns them as a new heap.
+    ///
+    /// Both heaps are rebuilt from the partitioned elements which invalidates all handles.
+    pub fn split_off(&mut self, threshold: K) -> PairingHeap<T, K, Ix> {
+        let mut upper = self.empty_like();
+        let lower = self.empty_like();
+        let old = ::std::mem::replace(self, lower);
+        for (elem, key) in old.into_pairs() {
+            if self.cmp_keys(&key, &threshold) == Ordering::Less {
+                self.push(elem, key);
+            } else {
+                upper.push(elem, key);
             }
         }
+        upper
+    }
+
+    /// Consumes this `PairingHeap` and returns its elements together with their keys
+    /// in unspecified order.
+    pub(crate) fn into_pairs(self) -> impl Iterator<Item = (T, K)> {
+        self.nodes.into_values().map(|node| (node.elem, node.key))
+    }
+
+    /// Returns the number of roots of this `PairingHeap`.
+    ///
+    /// Pushed elements stay roots until the next `pop` pairs them up.
+    ///
+    /// This walks the sibling ring of the roots and therefore takes `O(roots)`.
+    pub fn root_count(&self) -> usize {
+        match self.min {
+            None => 0,
+            Some(min) => self.siblings(min).count(),
+        }
+    }
+
+    /// Returns the number of edges on the longest path from a root to a leaf.
+    ///
+    /// Empty heaps and heaps consisting only of roots have a maximum depth of `0`.
+    pub fn max_depth(&self) -> usize {
+        let mut max_depth = 0;
+        let mut stack = match self.min {
+            None => return 0,
+            Some(min) => self.siblings(min).map(|root| (root, 0)).collect::<Vec<_>>(),
+        };
+        while let Some((handle, depth)) = stack.pop() {
+            max_depth = ::std::cmp::max(max_depth, depth);
+            stack.extend(self.children(handle).map(|child| (child, depth + 1)));
+        }
+        max_depth
+    }
+
+    /// Returns a measure of how balanced the trees of this `PairingHeap` currently are.
+    ///
+    /// The measure is the length of the longest path from a root to a leaf divided by
+    /// the binary logarithm of the number of elements. Values around `1.0` indicate
+    /// well balanced trees while large values indicate degenerated chains which
+    /// might be resolved with `consolidate`.
+    ///
+    /// Heaps with less than two elements have a balance factor of `1.0`.
+    pub fn balance_factor(&self) -> f64 {
+        if self.len() < 2 {
+            return 1.0;
+        }
+        self.max_depth() as f64 / (self.len() as f64).log2()
+    }
+
+    /// Returns `true` if the given handle is associated with an element of this `PairingHeap`.
+    #[inline]
+    pub fn contains(&self, handle: Handle<Ix>) -> bool {
+        self.lookup(handle).is_some()
     }
 
     /// Returns a reference to the element associated with the given handle.
     #[inline]
-    pub fn get(&self, handle: Handle) -> Option<&T> {
-        self.elems.get(handle)
+    pub fn get(&self, handle: Handle<Ix>) -> Option<&T> {
+        self.lookup(handle).map(|node| &node.elem)
+    }
+
+    /// Returns a reference to the element associated with the given handle
+    /// or the reason why the handle is not associated with an element.
+    ///
+    /// In contrast to `get` this tells handles whose index was never handed out by this
+    /// `PairingHeap` apart from handles whose element has been removed. Handles of a
+    /// `PairingHeap` that has been cleared since are reported as out of range.
+    pub fn get_checked(&self, handle: Handle<Ix>) -> ::std::result::Result<&T, HandleError> {
+        if usize::from(handle) >= self.slots {
+            return Err(HandleError::OutOfRange);
+        }
+        self.get(handle).ok_or(HandleError::Vacant)
     }
 
     /// Returns a mutable reference to the element associated with the given handle.
     #[inline]
-    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
-        self.elems.get_mut(handle)
+    pub fn get_mut(&mut self, handle: Handle<Ix>) -> Option<&mut T> {
+        if !self.contains(handle) {
+            return None;
+        }
+        Some(&mut self.node_mut(handle).elem)
+    }
+
+    /// Replaces the element associated with the given handle by `elem`
+    /// and returns the replaced element.
+    ///
+    /// The key of the element stays untouched.
+    /// Returns `None` if the handle is not associated with an element.
+    #[inline]
+    pub fn replace(&mut self, handle: Handle<Ix>, elem: T) -> Option<T> {
+        self.get_mut(handle)
+            .map(|slot| ::std::mem::replace(slot, elem))
+    }
+
+    /// Swaps the elements associated with the handles `a` and `b`.
+    ///
+    /// The keys and positions within the heap stay untouched.
+    /// Returns `false` and does nothing if any of the handles is not associated with an element.
+    pub fn swap_values(&mut self, a: Handle<Ix>, b: Handle<Ix>) -> bool {
+        if !self.contains(a) || !self.contains(b) {
+            return false;
+        }
+        if a != b {
+            // Moving `b` out of its slot and back keeps it in place since the slot
+            // freed last is the first one to be filled again.
+            let mut node = self
+                .nodes
+                .take(b)
+                .expect("handle must be associated with an element");
+            ::std::mem::swap(&mut node.elem, &mut self.node_mut(a).elem);
+            let restored = self.nodes.put(node);
+            debug_assert_eq!(usize::from(b), usize::from(restored));
+        }
+        true
+    }
+
+    /// Returns mutable references to the elements associated with the handles `a` and `b`.
+    ///
+    /// Returns `None` if `a` and `b` are equal or if any of them is not associated with an element.
+    ///
+    /// Both references are picked in a single pass over all elements which takes `O(n)`.
+    pub fn get_pair_mut(&mut self, a: Handle<Ix>, b: Handle<Ix>) -> Option<(&mut T, &mut T)> {
+        if a == b || !self.contains(a) || !self.contains(b) {
+            return None;
+        }
+        let (a, b) = (usize::from(a), usize::from(b));
+        let (mut first, mut second) = (None, None);
+        for (handle, node) in self.nodes.iter_mut() {
+            let index = usize::from(handle);
+            if index == a {
+                first = Some(&mut node.elem);
+            } else if index == b {
+                second = Some(&mut node.elem);
+            }
+        }
+        first.zip(second)
+    }
+
+    /// Returns the key of the element associated with the given handle.
+    #[inline]
+    pub fn get_key(&self, handle: Handle<Ix>) -> Option<K> {
+        self.lookup(handle).map(|node| node.key.clone())
     }
 
     /// Returns a reference to the element associated with the given handle.
@@ -558,8 +1912,8 @@ where
     /// This is unsafe because the handle is not checked to access elements within the bounds of the
     /// heap.
     #[inline]
-    pub unsafe fn get_unchecked(&self, handle: Handle) -> &T {
-        self.elems.get_unchecked(handle)
+    pub unsafe fn get_unchecked(&self, handle: Handle<Ix>) -> &T {
+        &self.node(handle).elem
     }
 
     /// Returns a mutable reference to the element associated with the given handle.
@@ -571,180 +1925,2940 @@ where
     /// This is unsafe because the handle is not checked to access elements within the bounds of the
     /// heap.
     #[inline]
-    pub unsafe fn get_unchecked_mut(&mut self, handle: Handle) -> &mut T {
-        self.elems.get_unchecked_mut(handle)
+    pub unsafe fn get_unchecked_mut(&mut self, handle: Handle<Ix>) -> &mut T {
+        &mut self.node_mut(handle).elem
+    }
+
+    /// Returns a reference to the current minimum element if not empty.
+    #[inline]
+    pub fn peek(&self) -> Option<&T> {
+        match self.min {
+            Some(min) => self.get(min),
+            None => None,
+        }
+    }
+
+    /// Returns the handle of the current minimum element if not empty.
+    #[inline]
+    pub fn peek_handle(&self) -> Option<Handle<Ix>> {
+        self.min
+    }
+
+    /// Returns a reference to the key of the current minimum element if not empty.
+    #[inline]
+    fn min_key(&self) -> Option<&K> {
+        self.peek_handle().map(|min| &self.node(min).key)
+    }
+
+    /// Returns a reference to the current minimum element.
+    ///
+    /// Does not perform bounds checking so use it carefully!
+    ///
+    /// # Safety
+    ///
+    /// This is unsafe because the heap is not checked to be non-empty.
+    #[inline]
+    pub unsafe fn peek_unchecked(&self) -> &T {
+        match self.min {
+            Some(min) => self.get_unchecked(min),
+            None => ::unreachable::unreachable(),
+        }
+    }
+
+    /// Returns a mutable reference to the current minimum element if not empty.
+    #[inline]
+    pub fn peek_mut(&mut self) -> Option<&mut T> {
+        match self.min {
+            Some(min) => self.get_mut(min),
+            None => None,
+        }
+    }
+
+    /// Sets the key of the current minimum element to `new_key`.
+    ///
+    /// If the key is increased the element is moved down in the heap so that another
+    /// element might become the new minimum.
+    ///
+    /// Returns `false` and does nothing if this `PairingHeap` is empty.
+    pub fn peek_mut_key(&mut self, new_key: K) -> bool {
+        match self.peek_handle() {
+            Some(min) => {
+                self.set_key(min, new_key)
+                    .expect("the minimum element must be live");
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns a reference to the current minimum element without bounds checking.
+    /// So use it very carefully!
+    ///
+    /// # Safety
+    ///
+    /// This is unsafe because the heap is not checked to be non-empty.
+    #[inline]
+    pub unsafe fn peek_unchecked_mut(&mut self) -> &mut T {
+        match self.min {
+            Some(min) => self.get_unchecked_mut(min),
+            None => ::unreachable::unreachable(),
+        }
+    }
+
+    /// Returns the number of `pop` calls that are required until the element associated
+    /// with the given `handle` is removed or `None` if there is no such element.
+    ///
+    /// Elements with a key equal to the key of the given element are not counted
+    /// since their order relative to the given element is unspecified
+    /// unless this `PairingHeap` is stable.
+    ///
+    /// This operation runs in `O(n)`.
+    pub fn pops_until(&self, handle: Handle<Ix>) -> Option<usize> {
+        let target = self.lookup(handle)?;
+        Some(
+            self.nodes
+                .values()
+                .filter(|node| self.cmp_nodes(node, target) == Ordering::Less)
+                .count(),
+        )
+    }
+
+    /// Iterate over the handles, keys and values in this `PairingHeap` by reference
+    /// in unspecified order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T, K, Ix> {
+        Iter {
+            iter: self.nodes.iter(),
+        }
+    }
+
+    /// Iterate over the `k` elements with the smallest keys in ascending order of their keys.
+    ///
+    /// The heap is not modified. Instead candidates are taken from an auxiliary binary heap
+    /// that starts out with the roots and is extended by the children of every yielded element.
+    /// This takes `O(r + k log(r + k))` where `r` is the number of roots.
+    pub fn iter_k_smallest(&self, k: usize) -> impl Iterator<Item = &T> + '_ {
+        let candidate = move |handle: Handle<Ix>| Candidate {
+            node: self.node(handle),
+            handle,
+            heap: self,
+        };
+        let mut candidates = self
+            .min
+            .into_iter()
+            .flat_map(|min| self.siblings(min))
+            .map(candidate)
+            .collect::<BinaryHeap<_>>();
+        ::std::iter::from_fn(move || {
+            let next = candidates.pop()?;
+            candidates.extend(self.children(next.handle).map(candidate));
+            Some(&self.node(next.handle).elem)
+        })
+        .take(k)
+    }
+
+    /// Returns a reference to the element with the `n`-th smallest key counting from `0`.
+    ///
+    /// The heap is not modified. Like `iter_k_smallest` this takes `O(r + n log(r + n))`
+    /// where `r` is the number of roots. Returns `None` if there are not more than `n` elements.
+    pub fn nth_smallest(&self, n: usize) -> Option<&T> {
+        self.iter_k_smallest(n.saturating_add(1)).nth(n)
+    }
+
+    /// Returns a reference to the element with the second smallest key.
+    ///
+    /// The heap is not modified. The second smallest element is either a root or a child
+    /// of the minimum element so only those are scanned. Returns `None` if there are
+    /// less than two elements.
+    #[inline]
+    pub fn peek_second(&self) -> Option<&T> {
+        self.nth_smallest(1)
+    }
+
+    /// Iterate over all elements in ascending order of their keys.
+    ///
+    /// The heap is neither consumed nor reordered which allows to inspect a snapshot
+    /// of the queue in priority order. See `iter_k_smallest` for the complexity.
+    #[inline]
+    pub fn iter_sorted(&self) -> impl Iterator<Item = &T> + '_ {
+        self.iter_k_smallest(self.len())
+    }
+
+    /// Iterate over the handles of all elements in this `PairingHeap` in unspecified order.
+    #[inline]
+    pub fn handles(&self) -> impl Iterator<Item = Handle<Ix>> + '_ {
+        self.nodes
+            .iter()
+            .map(|(index, node)| index.with_generation(node.gen))
+    }
+
+    /// Iterate over the keys in this `PairingHeap` by reference in unspecified order.
+    #[inline]
+    pub fn keys(&self) -> impl Iterator<Item = &K> + '_ {
+        self.nodes.values().map(|node| &node.key)
+    }
+
+    /// Iterate over the values in this `PairingHeap` by reference in unspecified order.
+    #[inline]
+    pub fn values(&self) -> Values<'_, T, K, Ix> {
+        Values {
+            nodes: &self.nodes,
+            next: 0,
+            len: self.nodes.len(),
+        }
+    }
+
+    /// Iterate over the values in this `PairingHeap` by reference together with copies
+    /// of their keys in unspecified order.
+    #[inline]
+    pub fn values_with_keys(&self) -> impl Iterator<Item = (&T, K)> + '_ {
+        self.iter().map(|(_, key, elem)| (elem, key.clone()))
+    }
+
+    /// Iterate over the values in this `PairingHeap` by mutable reference unspecified order.
+    #[inline]
+    pub fn values_mut(&mut self) -> ValuesMut<'_, T, K, Ix> {
+        ValuesMut {
+            iter: self.nodes.values_mut(),
+        }
+    }
+
+    /// Removes all elements from this `PairingHeap` and iterates over them in unspecified order.
+    ///
+    /// This is cheaper than `drain_min` since the elements are not paired up while draining.
+    /// The heap is empty afterwards and can be reused even if the `Drain` is not exhausted,
+    /// in which case the remaining elements are dropped together with it.
+    /// All handles are invalidated and the storage of the elements is released.
+    pub fn drain(&mut self) -> Drain<'_, T, K, Ix> {
+        let nodes = ::std::mem::take(&mut self.nodes);
+        self.clear();
+        Drain {
+            iter: nodes.into_values(),
+            marker: ::std::marker::PhantomData,
+        }
+    }
+
+    /// Iterate over values stored within a `PairingHeap` in a sorted-by-min order. Drains the heap.
+    #[inline]
+    pub fn drain_min(self) -> DrainMin<T, K, Ix> {
+        DrainMin { heap: self }
+    }
+
+    /// Consumes this `PairingHeap` and returns a vector of its values in ascending key order.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        let mut sorted = Vec::with_capacity(self.len());
+        sorted.extend(self.drain_min());
+        sorted
+    }
+
+    /// Consumes this `PairingHeap` and returns a vector of its values in unspecified order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.into_pairs().map(|(elem, _)| elem).collect()
+    }
+
+    /// Returns a textual dump of all stored elements with one `key: value` line per element.
+    ///
+    /// Lines are sorted ascending by key according to the comparator of this `PairingHeap`
+    /// and ties are broken by the rendered value, so the output does not depend on the
+    /// internal structure of the heap.
+    pub fn dump_sorted(&self) -> String
+    where
+        K: Display,
+        T: Display,
+    {
+        let mut entries = self
+            .nodes
+            .values()
+            .map(|node| (&node.key, node.elem.to_string()))
+            .collect::<Vec<_>>();
+        entries.sort_by(|lhs, rhs| self.cmp_keys(lhs.0, rhs.0).then_with(|| lhs.1.cmp(&rhs.1)));
+        entries
+            .into_iter()
+            .map(|(key, elem)| format!("{}: {}\n", key, elem))
+            .collect()
+    }
+
+    /// Returns a Graphviz DOT description of the trees of this `PairingHeap`.
+    ///
+    /// Every element is rendered as a node labeled with its key and handle index and every
+    /// parent is connected to each of its children by an edge. The minimum element is drawn bold.
+    pub fn to_dot(&self) -> String
+    where
+        K: Display,
+    {
+        let mut dot = String::from("digraph PairingHeap {\n");
+        for (handle, node) in self.nodes.iter() {
+            let handle = usize::from(handle);
+            let label = format!("{} (#{})", node.key, handle).replace('"', "\\\"");
+            let style = if self.min.map(usize::from) == Some(handle) {
+                ", style=bold"
+            } else {
+                ""
+            };
+            dot.push_str(&format!(
+                "    n{} [label=\"{}\"{}];\n",
+                handle, label, style
+            ));
+        }
+        for (handle, node) in self.nodes.iter() {
+            if let Some(parent) = node.parent {
+                dot.push_str(&format!(
+                    "    n{} -> n{};\n",
+                    usize::from(parent),
+                    usize::from(handle)
+                ));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+use std::ops::{Index, IndexMut};
+
+/// Formats the fields of the `PairingHeap` with `{:?}`.
+///
+/// The alternate flag `{:#?}` instead renders the trees as indented `key: element` lines,
+/// starting at the minimum element and with every child indented below its parent.
+impl<T, K, Ix> ::std::fmt::Debug for PairingHeap<T, K, Ix>
+where
+    T: ::std::fmt::Debug,
+    K: Key + ::std::fmt::Debug,
+    Ix: IndexType,
+{
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        if !f.alternate() {
+            return f
+                .debug_struct("PairingHeap")
+                .field("min", &self.min)
+                .field("nodes", &self.nodes)
+                .field("strategy", &self.strategy)
+                .field("cmp", &self.cmp.as_ref().map(|_| "custom"))
+                .field("stable", &self.stable)
+                .field("max_len", &self.max_len)
+                .field("seq", &self.seq)
+                .field("slots", &self.slots)
+                .finish();
+        }
+        writeln!(f, "PairingHeap {{")?;
+        let mut stack = match self.min {
+            Some(min) => self.siblings(min).map(|root| (root, 1)).collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+        stack.reverse();
+        while let Some((handle, depth)) = stack.pop() {
+            let node = self.node(handle);
+            writeln!(
+                f,
+                "{:indent$}{:?}: {:?}",
+                "",
+                node.key,
+                node.elem,
+                indent = 4 * depth
+            )?;
+            let children = stack.len();
+            stack.extend(self.children(handle).map(|child| (child, depth + 1)));
+            stack[children..].reverse();
+        }
+        write!(f, "}}")
+    }
+}
+
+/// Converts a `vec_heap::PairingHeap` into this representation.
+///
+/// The strategy, comparator and stability of the converted heap are kept while its handles
+/// are not carried over. Stable heaps are drained in sorted order so that elements with
+/// equal keys keep their relative order, all other heaps are drained in unspecified order.
+impl<T, K> From<::vec_heap::PairingHeap<T, K>> for PairingHeap<T, K>
+where
+    K: Key,
+{
+    fn from(mut heap: ::vec_heap::PairingHeap<T, K>) -> Self {
+        let strategy = match heap.strategy() {
+            ::vec_heap::PairingStrategy::SinglePass => PairingStrategy::SinglePass,
+            ::vec_heap::PairingStrategy::TwoPass => PairingStrategy::TwoPass,
+            ::vec_heap::PairingStrategy::MultiPass => PairingStrategy::MultiPass,
+        };
+        let mut converted = PairingHeap {
+            strategy,
+            cmp: heap.comparator().cloned(),
+            stable: heap.is_stable(),
+            ..Self::default()
+        };
+        converted.reserve(heap.len());
+        if heap.is_stable() {
+            while let Some(key) = heap.peek_key_cloned() {
+                let elem = heap.pop().expect("peeked element must be poppable");
+                converted.push(elem, key);
+            }
+        } else {
+            for (elem, key) in heap.into_pairs() {
+                converted.push(elem, key);
+            }
+        }
+        converted
+    }
+}
+
+impl<T, K, Ix> ::addressable_heap::AddressableHeap<T, K> for PairingHeap<T, K, Ix>
+where
+    K: Key,
+    Ix: IndexType,
+{
+    type Handle = Handle<Ix>;
+    type Error = Error;
+
+    #[inline]
+    fn push(&mut self, elem: T, key: K) -> Handle<Ix> {
+        PairingHeap::push(self, elem, key)
+    }
+
+    #[inline]
+    fn pop(&mut self) -> Option<T> {
+        PairingHeap::pop(self)
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<&T> {
+        PairingHeap::peek(self)
+    }
+
+    #[inline]
+    fn decrease_key(&mut self, handle: Handle<Ix>, new_key: K) -> Result<()> {
+        PairingHeap::decrease_key(self, handle, new_key)
+    }
+
+    #[inline]
+    fn get(&self, handle: Handle<Ix>) -> Option<&T> {
+        PairingHeap::get(self, handle)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        PairingHeap::len(self)
+    }
+}
+
+impl<T, K, Ix> PartialEq for PairingHeap<T, K, Ix>
+where
+    T: PartialEq,
+    K: Key,
+    Ix: IndexType,
+{
+    /// Compares both heaps as multisets of their element and key pairs.
+    ///
+    /// The shape of the heaps, their strategies and comparators are not taken into account.
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        fn sorted_entries<T, K: Key, Ix: IndexType>(heap: &PairingHeap<T, K, Ix>) -> Vec<(&K, &T)> {
+            let mut entries = heap
+                .iter()
+                .map(|(_, key, elem)| (key, elem))
+                .collect::<Vec<_>>();
+            entries.sort_by(|lhs, rhs| lhs.0.cmp(rhs.0));
+            entries
+        }
+        let lhs = sorted_entries(self);
+        let rhs = sorted_entries(other);
+        // Elements are only `PartialEq` so they are matched pairwise within runs of equal keys.
+        let mut start = 0;
+        while start < lhs.len() {
+            let key = lhs[start].0;
+            let end = start
+                + lhs[start..]
+                    .iter()
+                    .take_while(|entry| entry.0 == key)
+                    .count();
+            if rhs[start..end].iter().any(|entry| entry.0 != key) {
+                return false;
+            }
+            let mut unmatched = rhs[start..end]
+                .iter()
+                .map(|entry| entry.1)
+                .collect::<Vec<_>>();
+            for entry in &lhs[start..end] {
+                match unmatched.iter().position(|&elem| elem == entry.1) {
+                    Some(pos) => {
+                        unmatched.swap_remove(pos);
+                    }
+                    None => return false,
+                }
+            }
+            start = end;
+        }
+        true
+    }
+}
+
+impl<T, K, Ix> Eq for PairingHeap<T, K, Ix>
+where
+    T: Eq,
+    K: Key,
+    Ix: IndexType,
+{
+}
+
+impl<T, K, Ix> Index<Handle<Ix>> for PairingHeap<T, K, Ix>
+where
+    K: Key,
+    Ix: IndexType,
+{
+    type Output = T;
+
+    fn index(&self, handle: Handle<Ix>) -> &Self::Output {
+        self.get(handle).expect("no node found for given handle")
+    }
+}
+
+impl<T, K, Ix> IndexMut<Handle<Ix>> for PairingHeap<T, K, Ix>
+where
+    K: Key,
+    Ix: IndexType,
+{
+    fn index_mut(&mut self, handle: Handle<Ix>) -> &mut Self::Output {
+        self.get_mut(handle)
+            .expect("no node found for given handle")
+    }
+}
+
+impl<T, K, Ix> ::std::iter::FromIterator<(T, K)> for PairingHeap<T, K, Ix>
+where
+    K: Key,
+    Ix: IndexType,
+{
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (T, K)>,
+    {
+        let mut heap = PairingHeap::default();
+        heap.extend(iter);
+        heap
+    }
+}
+
+impl<T, K, Ix> Extend<(T, K)> for PairingHeap<T, K, Ix>
+where
+    K: Key,
+    Ix: IndexType,
+{
+    fn extend<I>(&mut self, iter: I)
+    where
+        I: IntoIterator<Item = (T, K)>,
+    {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (elem, key) in iter {
+            self.push(elem, key);
+        }
+    }
+}
+
+impl<T, K, Ix> IntoIterator for PairingHeap<T, K, Ix>
+where
+    K: Key,
+    Ix: IndexType,
+{
+    type Item = T;
+    type IntoIter = DrainMin<T, K, Ix>;
+
+    /// Iterates over the values of this `PairingHeap` in a sorted-by-min order.
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.drain_min()
+    }
+}
+
+impl<'a, T, K, Ix> IntoIterator for &'a PairingHeap<T, K, Ix>
+where
+    K: Key,
+    Ix: IndexType,
+{
+    type Item = &'a T;
+    type IntoIter = Values<'a, T, K, Ix>;
+
+    /// Iterates over references to the values of this `PairingHeap` in unspecified order.
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.values()
+    }
+}
+
+/// Iterator over the handles, keys and references to values stored within a `PairingHeap`.
+pub struct Iter<'a, T: 'a, K: 'a + Key, Ix: 'a + IndexType = usize> {
+    iter: ::stash::stash::Iter<'a, Node<T, K, Ix>, Handle<Ix>>,
+}
+
+impl<'a, T, K: Key, Ix: IndexType> Iterator for Iter<'a, T, K, Ix> {
+    type Item = (Handle<Ix>, &'a K, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(index, node)| (index.with_generation(node.gen), &node.key, &node.elem))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T, K: Key, Ix: IndexType> ExactSizeIterator for Iter<'a, T, K, Ix> {}
+
+/// Iterator over references to values stored within a `PairingHeap`.
+///
+/// The iterator walks the slots of the underlying storage by itself so that it can be
+/// cloned cheaply and the clone continues from the same position.
+pub struct Values<'a, T: 'a, K: 'a + Key, Ix: 'a + IndexType = usize> {
+    nodes: &'a Stash<Node<T, K, Ix>, Handle<Ix>>,
+    next: usize,
+    len: usize,
+}
+
+impl<'a, T, K: Key, Ix: IndexType> Clone for Values<'a, T, K, Ix> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Values {
+            nodes: self.nodes,
+            next: self.next,
+            len: self.len,
+        }
+    }
+}
+
+/// Iterator over mutable references to values stored within a `PairingHeap`.
+pub struct ValuesMut<'a, T: 'a, K: 'a + Key, Ix: 'a + IndexType = usize> {
+    iter: ::stash::stash::ValuesMut<'a, Node<T, K, Ix>>,
+}
+
+impl<'a, T, K: Key, Ix: IndexType> Iterator for Values<'a, T, K, Ix> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.len > 0 {
+            let index = self.next;
+            self.next += 1;
+            if let Some(node) = self.nodes.get(Handle::from(index)) {
+                self.len -= 1;
+                return Some(&node.elem);
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, K: Key, Ix: IndexType> ExactSizeIterator for Values<'a, T, K, Ix> {}
+
+impl<'a, T, K: Key, Ix: IndexType> Iterator for ValuesMut<'a, T, K, Ix> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|node| &mut node.elem)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T, K: Key, Ix: IndexType> ExactSizeIterator for ValuesMut<'a, T, K, Ix> {}
+
+/// Iterator over the values removed from a `PairingHeap` by `drain` in unspecified order.
+pub struct Drain<'a, T: 'a, K: 'a + Key, Ix: 'a + IndexType = usize> {
+    iter: ::stash::stash::IntoValues<Node<T, K, Ix>>,
+    marker: ::std::marker::PhantomData<&'a mut PairingHeap<T, K, Ix>>,
+}
+
+impl<'a, T, K: Key, Ix: IndexType> Iterator for Drain<'a, T, K, Ix> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|node| node.elem)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T, K: Key, Ix: IndexType> ExactSizeIterator for Drain<'a, T, K, Ix> {}
+
+impl<'a, T, K: Key, Ix: IndexType> ::std::iter::FusedIterator for Drain<'a, T, K, Ix> {}
+
+/// Iterator over values stored within a `PairingHeap` in a sorted-by-min order. Drains the heap.
+pub struct DrainMin<T, K: Key, Ix: IndexType = usize> {
+    heap: PairingHeap<T, K, Ix>,
+}
+
+impl<T, K: Key, Ix: IndexType> Iterator for DrainMin<T, K, Ix> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.heap.pop()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.heap.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, K: Key, Ix: IndexType> ExactSizeIterator for DrainMin<T, K, Ix> {}
+
+impl<T, K: Key, Ix: IndexType> ::std::iter::FusedIterator for DrainMin<T, K, Ix> {}
+
+/// Lazily drains the given heaps and yields their elements in ascending order of their keys
+/// across all heaps.
+///
+/// In contrast to `PairingHeap::meld_all` the heaps are not melded. Instead every step pops
+/// from the heap whose minimum key is the smallest which takes `O(k)` comparisons for `k`
+/// heaps besides the `pop`. Elements with equal keys are yielded from earlier heaps first.
+///
+/// All heaps are expected to order their keys with the same comparator.
+pub fn merge_drain<T, K, Ix>(mut heaps: Vec<PairingHeap<T, K, Ix>>) -> impl Iterator<Item = T>
+where
+    K: Key,
+    Ix: IndexType,
+{
+    heaps.retain(|heap| !heap.is_empty());
+    ::std::iter::from_fn(move || {
+        let mut best = 0;
+        for idx in 1..heaps.len() {
+            let lhs = heaps[idx].min_key()?;
+            let rhs = heaps[best].min_key()?;
+            if heaps[best].cmp_keys(lhs, rhs) == Ordering::Less {
+                best = idx;
+            }
+        }
+        let elem = heaps.get_mut(best)?.pop();
+        if heaps[best].is_empty() {
+            heaps.remove(best);
+        }
+        elem
+    })
+}
+
+/// Maps handles of a `PairingHeap` that got absorbed by `meld` or compacted
+/// by `shrink_to_fit` to their new handles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remapping<Ix: IndexType = usize> {
+    absorbed_self: bool,
+    handles: Vec<Option<Handle<Ix>>>,
+}
+
+impl<Ix: IndexType> Remapping<Ix> {
+    /// Returns `true` if the handles of the heap `meld` was called on got remapped
+    /// and `false` if the handles of the heap that was passed to `meld` got remapped.
+    ///
+    /// Always `true` for the `Remapping` returned by `shrink_to_fit`.
+    #[inline]
+    pub fn absorbed_self(&self) -> bool {
+        self.absorbed_self
+    }
+
+    /// Returns the new handle for the given handle of the absorbed heap.
+    ///
+    /// Returns `None` if the given handle was not associated with an element of the absorbed heap.
+    #[inline]
+    pub fn get(&self, handle: Handle<Ix>) -> Option<Handle<Ix>> {
+        self.handles
+            .get(usize::from(handle))
+            .and_then(|&handle| handle)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impls {
+    use super::{IndexType, Key, PairingHeap};
+    use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+    use serde::ser::{Serialize, Serializer};
+    use std::fmt;
+    use std::marker::PhantomData;
+    use std::mem::size_of;
+
+    /// Serializes the `PairingHeap` as a sequence of `(elem, key)` pairs in unspecified order.
+    ///
+    /// Neither handles nor the internal structure, strategy or comparator are serialized.
+    impl<T, K, Ix> Serialize for PairingHeap<T, K, Ix>
+    where
+        T: Serialize,
+        K: Key + Serialize,
+        Ix: IndexType,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.collect_seq(self.nodes.values().map(|node| (&node.elem, &node.key)))
+        }
+    }
+
+    /// Deserializes a `PairingHeap` from a sequence of `(elem, key)` pairs by pushing them
+    /// into a new `PairingHeap` with default strategy and natural key ordering.
+    impl<'de, T, K, Ix> Deserialize<'de> for PairingHeap<T, K, Ix>
+    where
+        T: Deserialize<'de>,
+        K: Key + Deserialize<'de>,
+        Ix: IndexType,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_seq(PairingHeapVisitor(PhantomData))
+        }
+    }
+
+    struct PairingHeapVisitor<T, K, Ix>(PhantomData<(T, K, Ix)>);
+
+    impl<'de, T, K, Ix> Visitor<'de> for PairingHeapVisitor<T, K, Ix>
+    where
+        T: Deserialize<'de>,
+        K: Key + Deserialize<'de>,
+        Ix: IndexType,
+    {
+        type Value = PairingHeap<T, K, Ix>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a sequence of (element, key) pairs")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut heap = PairingHeap::default();
+            // Do not trust the announced length beyond a megabyte of preallocated elements.
+            let cautious = (1 << 20) / ::std::cmp::max(1, size_of::<(T, K)>());
+            heap.reserve(::std::cmp::min(seq.size_hint().unwrap_or(0), cautious));
+            while let Some((elem, key)) = seq.next_element()? {
+                heap.push(elem, key);
+            }
+            Ok(heap)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn take_min() {
+        let mut ph = PairingHeap::new();
+        ph.push(0, 6);
+        ph.push(1, 10);
+        ph.push(2, -42);
+        ph.push(3, 1337);
+        ph.push(4, -1);
+        ph.push(5, 1);
+        ph.push(6, 2);
+        ph.push(7, 3);
+        ph.push(8, 4);
+        ph.push(9, 5);
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(2), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(4), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(5), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(6), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(7), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(8), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(9), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(0), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(1), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(Some(3), ph.pop());
+        println!("ph = {:?}", ph);
+        assert_eq!(None, ph.pop());
+        println!("ph = {:?}", ph);
+    }
+
+    #[test]
+    fn decrease_key_of_first_child() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..8).map(|n| ph.push(n, n)).collect::<Vec<_>>();
+        assert_eq!(Some(0), ph.pop());
+        for (n, &handle) in handles.iter().enumerate().skip(1) {
+            assert_eq!(Ok(()), ph.decrease_key(handle, -(n as i64)));
+        }
+        assert_eq!(
+            vec![7, 6, 5, 4, 3, 2, 1],
+            ph.drain_min().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn decrease_key() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push(0, 0);
+        let b = ph.push(1, 50);
+        let c = ph.push(2, 100);
+        let d = ph.push(3, 150);
+        let e = ph.push(4, 200);
+        let f = ph.push(5, 250);
+        assert_eq!(Some(&0), ph.peek());
+        assert_eq!(Ok(()), ph.decrease_key(f, -50));
+        assert_eq!(Some(&5), ph.peek());
+        assert_eq!(Ok(()), ph.decrease_key(e, -100));
+        assert_eq!(Some(&4), ph.peek());
+        assert_eq!(Ok(()), ph.decrease_key(d, -99));
+        assert_eq!(Some(&4), ph.peek());
+        assert_eq!(Err(Error::DecreaseKeyOutOfOrder), ph.decrease_key(c, 1000));
+        assert_eq!(Some(&4), ph.peek());
+        assert_eq!(Ok(()), ph.decrease_key(b, -1000));
+        assert_eq!(Some(&1), ph.peek());
+        assert_eq!(Err(Error::DecreaseKeyOutOfOrder), ph.decrease_key(a, 100));
+        assert_eq!(Some(&1), ph.peek());
+    }
+
+    #[test]
+    fn decrease_key_saturating() {
+        let mut ph = PairingHeap::<char, u32>::new();
+        let a = ph.push('a', 10);
+        let b = ph.push('b', 20);
+        let before = ph.clone();
+        assert_eq!(Ok(()), ph.decrease_key_saturating(b, 20));
+        assert_eq!(Ok(()), ph.decrease_key_saturating(b, 30));
+        assert!(before == ph);
+        assert_eq!(Some(20), ph.get_key(b));
+        assert_eq!(
+            Ok(()),
+            ph.decrease_key_saturating(b, 20u32.saturating_sub(25))
+        );
+        assert_eq!(Some(0), ph.get_key(b));
+        assert_eq!(Some(&'b'), ph.peek());
+        assert_eq!(Some('b'), ph.pop());
+        assert_eq!(Err(Error::InvalidHandle), ph.decrease_key_saturating(b, 0));
+        assert_eq!(Some(&'a'), ph.peek());
+        assert_eq!(Ok(()), ph.decrease_key_saturating(a, 9));
+        assert_eq!(Some(9), ph.get_key(a));
+    }
+
+    #[test]
+    fn decrease_key_by() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 10);
+        let b = ph.push('b', 20);
+        let c = ph.push('c', 30);
+        assert_eq!(Ok(()), ph.decrease_key_by(c, 25));
+        assert_eq!(Some(5), ph.get_key(c));
+        assert_eq!(Some(&'c'), ph.peek());
+        assert_eq!(Ok(()), ph.decrease_key_by(b, 12));
+        assert_eq!(Some(8), ph.get_key(b));
+        assert_eq!(Err(Error::DecreaseKeyOutOfOrder), ph.decrease_key_by(a, 0));
+        assert_eq!(Err(Error::DecreaseKeyOutOfOrder), ph.decrease_key_by(a, -1));
+        assert_eq!(Some(10), ph.get_key(a));
+        assert_eq!(vec!['c', 'b', 'a'], ph.pop_n(3));
+        assert_eq!(Err(Error::InvalidHandle), ph.decrease_key_by(a, 1));
+    }
+
+    #[test]
+    fn compare_and_decrease() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 10);
+        let b = ph.push('b', 20);
+        let c = ph.push('c', 30);
+        assert_eq!(Ok(()), ph.compare_and_decrease(c, 30, 15));
+        let before = ph.clone();
+        assert_eq!(Err(Error::KeyMismatch), ph.compare_and_decrease(c, 30, 5));
+        assert_eq!(Err(Error::KeyMismatch), ph.compare_and_decrease(b, 19, 5));
+        assert!(before == ph);
+        assert_eq!(Some(15), ph.get_key(c));
+        assert_eq!(Some(&'a'), ph.peek());
+        assert_eq!(
+            Err(Error::DecreaseKeyOutOfOrder),
+            ph.compare_and_decrease(b, 20, 20)
+        );
+        assert_eq!(Ok(()), ph.compare_and_decrease(b, 20, 5));
+        assert_eq!(vec!['b', 'a', 'c'], ph.pop_n(3));
+        assert_eq!(Err(Error::InvalidHandle), ph.compare_and_decrease(a, 10, 0));
+    }
+
+    #[test]
+    fn decrease_key_or_equal() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 10);
+        let b = ph.push('b', 20);
+        let c = ph.push('c', 30);
+        assert_eq!(Some('a'), ph.pop());
+        let before = ph.clone();
+        assert_eq!(Err(Error::DecreaseKeyOutOfOrder), ph.decrease_key(b, 20));
+        assert_eq!(Ok(()), ph.decrease_key_or_equal(b, 20));
+        assert_eq!(Ok(()), ph.decrease_key_or_equal(c, 30));
+        assert!(before == ph);
+        assert_eq!(Some(&'b'), ph.peek());
+        assert_eq!(
+            Err(Error::DecreaseKeyOutOfOrder),
+            ph.decrease_key_or_equal(b, 25)
+        );
+        assert_eq!(Ok(()), ph.decrease_key_or_equal(c, 5));
+        assert_eq!(Some(&'c'), ph.peek());
+        assert_eq!(Err(Error::InvalidHandle), ph.decrease_key_or_equal(a, 0));
+        assert_eq!(Some('c'), ph.pop());
+        assert_eq!(Some('b'), ph.pop());
+        assert_eq!(None, ph.pop());
+    }
+
+    #[test]
+    fn decrease_key_bulk() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..10).map(|i| ph.push(i, i * 10)).collect::<Vec<_>>();
+        assert_eq!(Some(0), ph.pop());
+        let updates = [
+            (handles[7], 5),
+            (handles[9], -3),
+            (handles[4], 1),
+            (handles[9], -4),
+        ];
+        assert_eq!(Ok(()), ph.decrease_key_bulk(&updates));
+        assert_eq!(Some(&9), ph.peek());
+        assert_eq!(Ok(()), ph.check_consistency());
+        let updates = [(handles[8], 2), (handles[3], 100), (handles[5], 0)];
+        assert_eq!(
+            Err(Error::DecreaseKeyOutOfOrderAt(1)),
+            ph.decrease_key_bulk(&updates)
+        );
+        assert_eq!(Some(2), ph.get_key(handles[8]));
+        assert_eq!(Some(50), ph.get_key(handles[5]));
+        assert_eq!(
+            Err(Error::InvalidHandleAt(0)),
+            ph.decrease_key_bulk(&[(handles[0], -10)])
+        );
+        assert_eq!(
+            "new key of update 1 must be less than the current key for decrease_key_bulk",
+            Error::DecreaseKeyOutOfOrderAt(1).to_string()
+        );
+        assert_eq!(vec![9, 4, 8, 7, 1, 2, 3, 5, 6], ph.into_sorted_vec());
+    }
+
+    #[test]
+    fn change_key() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..10).map(|i| ph.push(i, i * 10)).collect::<Vec<_>>();
+        assert_eq!(Some(0), ph.pop());
+        assert_eq!(Ok(()), ph.change_key(handles[5], -10));
+        assert_eq!(Some(&5), ph.peek());
+        assert_eq!(Ok(()), ph.change_key(handles[5], 1000));
+        assert_eq!(Some(&1), ph.peek());
+        assert_eq!(Ok(()), ph.change_key(handles[1], 55));
+        assert_eq!(Some(&2), ph.peek());
+        assert_eq!(Ok(()), ph.change_key(handles[9], 15));
+        assert_eq!(Ok(()), ph.change_key(handles[9], 15));
+        assert_eq!(Some(&9), ph.peek());
+        assert_eq!(Ok(()), ph.change_key(handles[3], 95));
+        assert_eq!(Ok(()), ph.change_key(handles[3], 5));
+        assert_eq!(Some(&3), ph.peek());
+        for _ in 0..5 {
+            assert_eq!(Ok(()), ph.change_key(handles[7], 500));
+            assert_eq!(Ok(()), ph.change_key(handles[7], 25));
+        }
+        assert_eq!(Some(3), ph.pop());
+        assert_eq!(Ok(()), ph.change_key(handles[8], 1));
+        assert_eq!(Ok(()), ph.change_key(handles[8], 80));
+        assert_eq!(
+            ph.drain_min().collect::<Vec<_>>(),
+            vec![9, 2, 7, 4, 1, 6, 8, 5]
+        );
+    }
+
+    #[test]
+    fn pops_until() {
+        let mut ph = PairingHeap::new();
+        let handles = [30, 10, 50, 20, 40]
+            .iter()
+            .map(|&key| ph.push(key, key))
+            .collect::<Vec<_>>();
+        assert_eq!(Some(0), ph.pops_until(handles[1]));
+        assert_eq!(Some(2), ph.pops_until(handles[0]));
+        assert_eq!(Some(4), ph.pops_until(handles[2]));
+        assert_eq!(Some(10), ph.pop());
+        assert_eq!(None, ph.pops_until(handles[1]));
+        assert_eq!(Some(1), ph.pops_until(handles[0]));
+        assert_eq!(Some(0), ph.pops_until(handles[3]));
+    }
+
+    #[test]
+    fn remove() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..20)
+            .map(|i| ph.push(i, (i * 7) % 20))
+            .collect::<Vec<_>>();
+        assert_eq!(Some(0), ph.pop());
+        assert_eq!(Some(3), ph.pop());
+        assert_eq!(None, ph.remove(handles[0]));
+        assert_eq!(Some(9), ph.remove(handles[9]));
+        assert_eq!(Some(17), ph.remove(handles[17]));
+        assert_eq!(None, ph.remove(handles[17]));
+        assert_eq!(Some(6), ph.remove(handles[6]));
+        assert_eq!(Some(&12), ph.peek());
+        assert_eq!(Some(13), ph.remove(handles[13]));
+        assert_eq!(14, ph.len());
+        assert_eq!(
+            ph.drain_min().collect::<Vec<_>>(),
+            vec![12, 15, 18, 1, 4, 7, 10, 16, 19, 2, 5, 8, 11, 14]
+        );
+    }
+
+    #[test]
+    fn remove_last() {
+        let mut ph = PairingHeap::new();
+        let handle = ph.push('a', 42);
+        assert_eq!(Some('a'), ph.remove(handle));
+        assert!(ph.is_empty());
+        assert_eq!(None, ph.peek());
+        assert_eq!(None, ph.pop());
+        ph.push('b', 7);
+        assert_eq!(Some(&'b'), ph.peek());
+    }
+
+    #[test]
+    fn meld() {
+        let mut small = PairingHeap::new();
+        let s = [
+            small.push('a', 10),
+            small.push('b', -5),
+            small.push('c', 30),
+        ];
+        let mut large = PairingHeap::new();
+        let l = (0..10)
+            .map(|i| large.push((b'k' + i as u8) as char, i * 7 - 20))
+            .collect::<Vec<_>>();
+        assert_eq!(Some('k'), large.pop());
+        let mut fst = small.clone();
+        let remapping = fst.meld(large.clone());
+        assert!(remapping.absorbed_self());
+        assert_eq!(Some(&'b'), fst.get(remapping.get(s[1]).unwrap()));
+        assert_eq!(None, remapping.get(Handle::from(3)));
+        assert_eq!(Some(&'t'), fst.get(l[9]));
+        assert_eq!(fst.drain_min().collect::<String>(), "lmbnoapqrcst");
+
+        let mut snd = large;
+        let remapping = snd.meld(small);
+        assert!(!remapping.absorbed_self());
+        assert_eq!(Some(&'c'), snd.get(remapping.get(s[2]).unwrap()));
+        assert_eq!(Some(&'m'), snd.get(l[2]));
+        assert_eq!(snd.drain_min().collect::<String>(), "lmbnoapqrcst");
+    }
+
+    #[test]
+    fn meld_keeps_configuration() {
+        let mut bounded = PairingHeap::with_max_len(8);
+        bounded.push('a', 1);
+        let mut large = PairingHeap::new();
+        for (elem, key) in "bcd".chars().zip(0..) {
+            large.push(elem, key);
+        }
+        assert!(bounded.meld(large.clone()).absorbed_self());
+        assert_eq!(Some(8), bounded.max_len());
+        assert!(!bounded.is_stable());
+
+        let mut stable = PairingHeap::new_stable();
+        stable.push('a', 1);
+        assert!(stable.meld(large).absorbed_self());
+        assert_eq!(None, stable.max_len());
+        assert!(stable.is_stable());
+        assert_eq!(vec!['b', 'a', 'c', 'd'], stable.pop_n(4));
+    }
+
+    #[test]
+    fn meld_empty() {
+        let mut ph = PairingHeap::new();
+        ph.meld(PairingHeap::new());
+        assert!(ph.is_empty());
+        let mut other = PairingHeap::new();
+        let handle = other.push('a', 1);
+        let remapping = ph.meld(other);
+        assert!(remapping.absorbed_self());
+        assert_eq!(Some(&'a'), ph.get(handle));
+        assert_eq!(Some('a'), ph.pop());
+        assert_eq!(None, ph.pop());
+    }
+
+    #[test]
+    fn split_into() {
+        let mut ph = PairingHeap::new();
+        for i in 0..100 {
+            ph.push(i, (i * 37) % 100);
+        }
+        assert_eq!(Some(0), ph.pop());
+        ph.push(0, 0);
+        let heaps = ph.split_into(4);
+        assert_eq!(4, heaps.len());
+        let mut elems = Vec::new();
+        for heap in heaps {
+            assert_eq!(25, heap.len());
+            let drained = heap.drain_min().collect::<Vec<_>>();
+            let keys = drained.iter().map(|i| (i * 37) % 100).collect::<Vec<_>>();
+            let mut sorted = keys.clone();
+            sorted.sort();
+            assert_eq!(keys, sorted);
+            elems.extend(drained);
+        }
+        elems.sort();
+        assert_eq!(elems, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn contains() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 1);
+        let b = ph.push('b', 2);
+        assert!(ph.contains(a));
+        assert!(ph.contains(b));
+        assert_eq!(Some('a'), ph.pop());
+        assert!(!ph.contains(a));
+        assert!(ph.contains(b));
+        assert_eq!(Some('b'), ph.pop());
+        assert!(!ph.contains(b));
+        assert!(!ph.contains(Handle::from(1337)));
+    }
+
+    #[test]
+    fn peek_cloned() {
+        let mut ph = PairingHeap::new();
+        assert_eq!(None, ph.peek_cloned());
+        assert_eq!(None, ph.peek_key_cloned());
+        ph.push(String::from("b"), 2);
+        ph.push(String::from("a"), 1);
+        let min = ph.peek_cloned();
+        assert_eq!(ph.peek(), min.as_ref());
+        assert_eq!(Some(1), ph.peek_key_cloned());
+        ph.push(String::from("c"), 0);
+        assert_eq!(Some(String::from("a")), min);
+        assert_eq!(Some(&String::from("c")), ph.peek());
+        assert_eq!(Some(0), ph.peek_key_cloned());
+    }
+
+    #[test]
+    fn clear() {
+        let mut ph = setup();
+        ph.clear();
+        assert_eq!(0, ph.len());
+        assert!(ph.is_empty());
+        assert_eq!(None, ph.peek());
+        assert_eq!(None, ph.pop());
+        let a = ph.push('a', 2);
+        let b = ph.push('b', 1);
+        assert_eq!(0, usize::from(a));
+        assert_eq!(1, usize::from(b));
+        assert_eq!(Some('b'), ph.pop());
+        assert_eq!(Some('a'), ph.pop());
+        assert_eq!(None, ph.pop());
+    }
+
+    #[test]
+    fn handles_aligned() {
+        let mut ph = PairingHeap::new();
+        let mut handles = Vec::new();
+        for i in 0..500 {
+            handles.push(ph.push(i, (i * 7919) % 211));
+            ph.assert_consistent();
+            let handle = handles[(i * 31) % handles.len()];
+            match i % 5 {
+                0 => {
+                    ph.pop();
+                }
+                1 if ph.contains(handle) => {
+                    ph.change_key(handle, (i * 13) % 211).unwrap();
+                }
+                2 => {
+                    ph.remove(handle);
+                }
+                _ => (),
+            }
+            ph.assert_consistent();
+        }
+        while ph.pop().is_some() {
+            ph.assert_consistent();
+        }
+    }
+
+    #[test]
+    fn balance_factor() {
+        let mut balanced = PairingHeap::new();
+        for i in 0..64 {
+            balanced.push(i, i);
+        }
+        balanced.set_strategy(PairingStrategy::MultiPass);
+        assert!(balanced.balance_factor() <= 1.0);
+        assert!(balanced.balance_factor() >= 0.5);
+
+        let mut chain = PairingHeap::new();
+        chain.set_strategy(PairingStrategy::MultiPass);
+        for i in 0..64 {
+            chain.push(i, -i);
+            chain.consolidate();
+        }
+        assert!(chain.balance_factor() > 5.0);
+        assert!(chain.balance_factor() > balanced.balance_factor());
+    }
+
+    #[test]
+    fn meld_dedup() {
+        let mut ph = PairingHeap::new();
+        ph.push("a", 1);
+        ph.push("b", 2);
+        ph.push("c", 3);
+        let mut other = PairingHeap::new();
+        other.push("b", 20);
+        other.push("d", 4);
+        other.push("a", 0);
+        other.push("d", 5);
+        ph.meld_dedup(other, |lhs, rhs| lhs == rhs);
+        assert_eq!(4, ph.len());
+        assert_eq!(Some("a"), ph.pop());
+        assert_eq!(Some("b"), ph.pop());
+        assert_eq!(Some("c"), ph.pop());
+        assert_eq!(Some("d"), ph.pop());
+        assert_eq!(None, ph.pop());
+    }
+
+    #[test]
+    fn reserve() {
+        let mut ph = setup();
+        let len = ph.len();
+        ph.reserve(100);
+        assert!(ph.capacity() >= len + 100);
+        ph.reserve_exact(1000);
+        assert!(ph.capacity() >= len + 1000);
+        let capacity = ph.capacity();
+        for i in 0..1000 {
+            ph.push('x', i);
+        }
+        assert_eq!(capacity, ph.capacity());
+    }
+
+    #[test]
+    fn swap() {
+        let mut fst = PairingHeap::new();
+        let a = fst.push('a', 2);
+        let b = fst.push('b', 1);
+        let mut snd = PairingHeap::new();
+        snd.set_strategy(PairingStrategy::MultiPass);
+        let x = snd.push('x', 10);
+        fst.swap(&mut snd);
+        assert_eq!(1, fst.len());
+        assert_eq!(2, snd.len());
+        assert_eq!(PairingStrategy::MultiPass, fst.strategy());
+        assert_eq!(PairingStrategy::SinglePass, snd.strategy());
+        assert_eq!(Some(&'x'), fst.get(x));
+        assert_eq!(Some(&'a'), snd.get(a));
+        assert_eq!(Ok(()), snd.decrease_key(a, 0));
+        assert_eq!(Some(&'a'), snd.peek());
+        assert_eq!(Some('x'), fst.pop());
+        assert_eq!(Some('a'), snd.pop());
+        assert_eq!(Some(&'b'), snd.get(b));
+        assert_eq!(Some('b'), snd.pop());
+    }
+
+    #[test]
+    fn swap_comparators() {
+        let mut min = PairingHeap::new();
+        let mut max = PairingHeap::with_comparator(|lhs: &i32, rhs: &i32| rhs.cmp(lhs));
+        for key in &[3, 1, 2] {
+            min.push(*key, *key);
+            max.push(*key, *key);
+        }
+        min.swap(&mut max);
+        assert_eq!(vec![3, 2, 1], min.pop_n(3));
+        assert_eq!(vec![1, 2, 3], max.pop_n(3));
+    }
+
+    #[test]
+    fn swap_stable() {
+        let mut stable = PairingHeap::new_stable();
+        let mut unstable = PairingHeap::new();
+        for (elem, key) in "abcd".chars().zip(&[1, 0, 1, 1]) {
+            stable.push(elem, *key);
+        }
+        unstable.push('x', 0);
+        stable.swap(&mut unstable);
+        assert!(!stable.is_stable());
+        assert!(unstable.is_stable());
+        assert_eq!(vec!['b', 'a', 'c', 'd'], unstable.pop_n(4));
+        assert_eq!(Some('x'), stable.pop());
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..10_000).map(|n| ph.push(n, n)).collect::<Vec<_>>();
+        for n in 0..9_900 {
+            assert_eq!(Some(n), ph.pop());
+        }
+        let remapping = ph.shrink_to_fit();
+        assert!(remapping.absorbed_self());
+        assert_eq!(100, ph.len());
+        assert!(ph.capacity() < 1_000);
+        let last = remapping.get(handles[9_999]).unwrap();
+        assert_eq!(Ok(()), ph.decrease_key(last, -1));
+        assert_eq!(Some(9_999), ph.pop());
+        for n in 9_900..9_999 {
+            assert_eq!(Some(n), ph.pop());
+        }
+        assert_eq!(None, ph.pop());
+    }
+
+    #[test]
+    fn shrink_to_fit_after_cuts() {
+        let mut ph = PairingHeap::with_strategy(PairingStrategy::TwoPass);
+        let handles = (0..10_000).map(|n| ph.push(n, n)).collect::<Vec<_>>();
+        assert_eq!(Some(0), ph.pop());
+        for (n, &handle) in handles.iter().enumerate().skip(1) {
+            if n % 100 != 0 {
+                assert!(ph.remove(handle).is_some());
+            } else {
+                assert_eq!(Ok(()), ph.decrease_key(handle, -(n as i64)));
+            }
+        }
+        let capacity = ph.capacity();
+        let remapping = ph.shrink_to_fit();
+        assert!(ph.capacity() < capacity / 10);
+        assert_eq!(Ok(()), ph.check_consistency());
+        assert_eq!(
+            Some(1),
+            ph.get(remapping.get(handles[100]).unwrap())
+                .map(|&n| n / 100)
+        );
+        let expected = (1..100).rev().map(|n| n * 100).collect::<Vec<_>>();
+        assert_eq!(expected, ph.into_sorted_vec());
+    }
+
+    #[test]
+    fn shrink_nodes_to_fit() {
+        let mut ph = PairingHeap::with_strategy(PairingStrategy::TwoPass);
+        let handles = (0..10_000).map(|n| ph.push(n, n)).collect::<Vec<_>>();
+        assert_eq!(Some(0), ph.pop());
+        for (n, &handle) in handles.iter().enumerate().skip(1) {
+            if n % 100 != 0 {
+                assert!(ph.remove(handle).is_some());
+            } else {
+                assert_eq!(Ok(()), ph.decrease_key(handle, -(n as i64)));
+            }
+        }
+        let capacity = ph.capacity();
+        let remapping = ph.shrink_nodes_to_fit();
+        assert!(ph.capacity() < capacity / 10);
+        assert_eq!(Ok(()), ph.check_consistency());
+        for (n, &handle) in handles.iter().enumerate().step_by(100).skip(1) {
+            let handle = remapping.get(handle).unwrap();
+            assert_eq!(Some(-(n as i64)), ph.get_key(handle));
+        }
+        let expected = (1..100).rev().map(|n| n * 100).collect::<Vec<_>>();
+        assert_eq!(expected, ph.clone().into_sorted_vec());
+        let handle = ph.push(42, -10_000);
+        assert_eq!(Some(&42), ph.get(handle));
+        assert_eq!(Some(42), ph.pop());
+        assert_eq!(expected, ph.pop_n(99));
+    }
+
+    #[test]
+    fn from_iter() {
+        let pairs = vec![('c', 3), ('a', 1), ('d', 4), ('b', 2)];
+        let ph = pairs.into_iter().collect::<PairingHeap<_, _>>();
+        assert_eq!(4, ph.len());
+        assert_eq!(vec!['a', 'b', 'c', 'd'], ph.drain_min().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn extend() {
+        let mut ph = PairingHeap::new();
+        ph.push('b', 2);
+        ph.push('e', 5);
+        ph.extend(vec![('d', 4), ('a', 1), ('c', 3)]);
+        assert_eq!(5, ph.len());
+        assert_eq!(
+            vec!['a', 'b', 'c', 'd', 'e'],
+            ph.drain_min().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn into_iter() {
+        let ph = (0..100)
+            .map(|n| (n, (n * 37) % 101))
+            .collect::<PairingHeap<_, _>>();
+        let mut keys = Vec::new();
+        for n in ph {
+            keys.push((n * 37) % 101);
+        }
+        assert_eq!(100, keys.len());
+        assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn into_sorted_vec() {
+        let keys = (0..200).map(|n| (n * 73) % 211 - 100).collect::<Vec<i64>>();
+        let ph = keys.iter().map(|&k| (k, k)).collect::<PairingHeap<_, _>>();
+        let mut expected = keys.clone();
+        expected.sort();
+        assert_eq!(expected, ph.into_sorted_vec());
+        assert!(PairingHeap::<i64, i64>::new().into_sorted_vec().is_empty());
+    }
+
+    #[test]
+    fn into_vec() {
+        let ph = vec![('c', 3), ('a', 1), ('a', 1), ('b', 2)]
+            .into_iter()
+            .collect::<PairingHeap<_, _>>();
+        let mut elems = ph.into_vec();
+        assert_eq!(4, elems.len());
+        elems.sort();
+        assert_eq!(vec!['a', 'a', 'b', 'c'], elems);
+    }
+
+    #[test]
+    fn peek_handle() {
+        let mut ph = PairingHeap::new();
+        assert_eq!(None, ph.peek_handle());
+        ph.push('b', 2);
+        let a = ph.push('a', 1);
+        let c = ph.push('c', 3);
+        assert_eq!(Some(a), ph.peek_handle());
+        assert_eq!(Some(&'a'), ph.get(ph.peek_handle().unwrap()));
+        assert_eq!(Ok(()), ph.decrease_key(c, 0));
+        assert_eq!(Some(c), ph.peek_handle());
+    }
+
+    #[test]
+    fn get_checked() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 1);
+        let b = ph.push('b', 2);
+        assert_eq!(Ok(&'a'), ph.get_checked(a));
+        assert_eq!(
+            Err(HandleError::OutOfRange),
+            ph.get_checked(Handle::from(9999))
+        );
+        assert_eq!(Some('a'), ph.pop());
+        assert_eq!(None, ph.get(a));
+        assert_eq!(Err(HandleError::Vacant), ph.get_checked(a));
+        assert_eq!(Ok(&'b'), ph.get_checked(b));
+        let mut other = PairingHeap::new();
+        ph.swap(&mut other);
+        assert_eq!(Err(HandleError::OutOfRange), ph.get_checked(b));
+        assert_eq!(Ok(&'b'), other.get_checked(b));
+        other.clear();
+        assert_eq!(Err(HandleError::OutOfRange), other.get_checked(b));
+    }
+
+    #[test]
+    fn peek_with_key() {
+        let mut ph = PairingHeap::new();
+        assert_eq!(None, ph.peek_with_key());
+        ph.push('b', 2);
+        let a = ph.push('a', 1);
+        let c = ph.push('c', 3);
+        assert_eq!(Some((&'a', 1)), ph.peek_with_key());
+        assert_eq!(Ok(()), ph.decrease_key(c, 0));
+        assert_eq!(ph.peek().zip(ph.peek_key()), ph.peek_with_key());
+        assert_eq!(Some((&'c', 0)), ph.peek_with_key());
+        assert_eq!(Some('c'), ph.pop());
+        ph.remove(a);
+        assert_eq!(Some((&'b', 2)), ph.peek_with_key());
+    }
+
+    #[test]
+    fn get_key() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 5);
+        let b = ph.push('b', -3);
+        assert_eq!(Some(5), ph.get_key(a));
+        assert_eq!(Some(-3), ph.get_key(b));
+        assert_eq!(Ok(()), ph.decrease_key(a, -7));
+        assert_eq!(Some(-7), ph.get_key(a));
+        assert_eq!(Some('a'), ph.pop());
+        assert_eq!(None, ph.get_key(a));
+    }
+
+    #[test]
+    fn peek_key() {
+        let mut ph = PairingHeap::new();
+        assert_eq!(None, ph.peek_key());
+        let a = ph.push('a', 10);
+        assert_eq!(Some(10), ph.peek_key());
+        let b = ph.push('b', 5);
+        ph.push('c', 7);
+        assert_eq!(Some(5), ph.peek_key());
+        assert_eq!(Ok(()), ph.decrease_key(a, 1));
+        assert_eq!(Some(1), ph.peek_key());
+        assert_eq!(Ok(()), ph.decrease_key(b, -2));
+        assert_eq!(Some(-2), ph.peek_key());
+        ph.pop();
+        assert_eq!(Some(1), ph.peek_key());
+    }
+
+    #[test]
+    fn retain() {
+        let mut ph = (0..20)
+            .map(|n| (n, (n * 7) % 20))
+            .collect::<PairingHeap<_, _>>();
+        ph.retain(|_, &key| key % 2 == 0);
+        assert_eq!(10, ph.len());
+        let keys = ph.drain_min().map(|n| (n * 7) % 20).collect::<Vec<_>>();
+        assert_eq!(vec![0, 2, 4, 6, 8, 10, 12, 14, 16, 18], keys);
+    }
+
+    #[test]
+    fn append() {
+        let mut fst = (0..10).map(|n| (n, 2 * n)).collect::<PairingHeap<_, _>>();
+        let mut snd = (10..20)
+            .map(|n| (n, 2 * (n - 10) + 1))
+            .collect::<PairingHeap<_, _>>();
+        fst.append(&mut snd);
+        assert!(snd.is_empty());
+        assert_eq!(20, fst.len());
+        let keys = fst
+            .drain_min()
+            .map(|n| if n < 10 { 2 * n } else { 2 * (n - 10) + 1 })
+            .collect::<Vec<_>>();
+        assert_eq!((0..20).collect::<Vec<_>>(), keys);
+        snd.push(42, 0);
+        assert_eq!(Some(42), snd.pop());
+    }
+
+    #[test]
+    fn with_capturing_comparator() {
+        let ranks = [2, 0, 1];
+        let mut ph = PairingHeap::with_comparator(move |lhs: &usize, rhs: &usize| {
+            ranks[*lhs].cmp(&ranks[*rhs])
+        });
+        ph.push('a', 0);
+        ph.push('b', 1);
+        ph.push('c', 2);
+        assert!(ph.comparator().is_some());
+        let clone = ph.clone();
+        assert_eq!(vec!['b', 'c', 'a'], ph.into_sorted_vec());
+        assert_eq!(vec!['b', 'c', 'a'], clone.into_sorted_vec());
+        assert!(PairingHeap::<char, usize>::new().comparator().is_none());
+    }
+
+    #[test]
+    fn with_comparator() {
+        let mut ph = PairingHeap::with_comparator(|lhs: &i64, rhs: &i64| rhs.cmp(lhs));
+        ph.push('a', 1);
+        let b = ph.push('b', 2);
+        ph.push('c', 3);
+        ph.push('d', 0);
+        assert_eq!(Some(&'c'), ph.peek());
+        assert_eq!(Err(Error::DecreaseKeyOutOfOrder), ph.decrease_key(b, 1));
+        assert_eq!(Ok(()), ph.decrease_key(b, 5));
+        assert_eq!(Some(&'b'), ph.peek());
+        assert_eq!(Ok(()), ph.change_key(b, -1));
+        assert_eq!("3: c\n1: a\n0: d\n-1: b\n", ph.dump_sorted());
+        assert_eq!(vec!['c', 'a', 'd', 'b'], ph.into_sorted_vec());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        use test_serde::round_trip;
+
+        let mut ph = PairingHeap::new();
+        let handles = (0..20)
+            .map(|n| ph.push(n, ((n * 7) % 20) as i32))
+            .collect::<Vec<_>>();
+        ph.pop();
+        assert_eq!(Ok(()), ph.decrease_key(handles[5], -5));
+        let restored: PairingHeap<i64, i64> = round_trip(&ph);
+        assert_eq!(ph.len(), restored.len());
+        assert_eq!(ph.dump_sorted(), restored.dump_sorted());
+        assert_eq!(ph.into_sorted_vec(), restored.into_sorted_vec());
+        let empty: PairingHeap<i64, i64> = round_trip(&PairingHeap::<i64, i64>::new());
+        assert!(empty.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_untrusted_len() {
+        use test_serde::{from_seq_with_len, Value};
+
+        let pairs = vec![
+            Value::Seq(vec![Value::Int(1), Value::Int(5)]),
+            Value::Seq(vec![Value::Int(2), Value::Int(3)]),
+        ];
+        let ph: PairingHeap<i64, i64> = from_seq_with_len(pairs, 1 << 40);
+        assert!(ph.capacity() <= 1 << 20);
+        assert_eq!(vec![2, 1], ph.into_sorted_vec());
+    }
+
+    #[test]
+    fn with_strategy() {
+        for &strategy in &[
+            PairingStrategy::SinglePass,
+            PairingStrategy::TwoPass,
+            PairingStrategy::MultiPass,
+        ] {
+            let mut ph = PairingHeap::with_strategy(strategy);
+            assert_eq!(strategy, ph.strategy());
+            let handles = (0..100)
+                .map(|n| ph.push(n, (n * 37) % 101))
+                .collect::<Vec<_>>();
+            ph.consolidate();
+            if strategy != PairingStrategy::SinglePass {
+                assert_eq!(1, ph.siblings(ph.min.unwrap()).count());
+            }
+            assert_eq!(Ok(()), ph.decrease_key(handles[50], -1));
+            assert_eq!(Some(50), ph.pop());
+            let keys = ph.drain_min().map(|n| (n * 37) % 101).collect::<Vec<_>>();
+            assert_eq!(99, keys.len());
+            assert!(keys.windows(2).all(|w| w[0] <= w[1]));
+        }
+    }
+
+    #[test]
+    fn drain() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..100)
+            .map(|n| ph.push(n, (n * 37) % 101))
+            .collect::<Vec<_>>();
+        assert_eq!(Some(0), ph.pop());
+        let drain = ph.drain();
+        assert_eq!(99, drain.len());
+        let mut drained = drain.collect::<Vec<_>>();
+        drained.sort();
+        assert_eq!((1..100).collect::<Vec<_>>(), drained);
+        assert!(ph.is_empty());
+        assert_eq!(None, ph.peek());
+        assert!(handles.iter().all(|&handle| !ph.contains(handle)));
+        ph.push(5, 5);
+        ph.push(3, 3);
+        assert_eq!(Some(3), ph.pop());
+        {
+            let mut drain = ph.drain();
+            assert_eq!(Some(5), drain.next());
+            assert_eq!(None, drain.next());
+        }
+        ph.push(1, 1);
+        ph.push(2, 2);
+        ph.drain().next();
+        assert!(ph.is_empty());
+        assert_eq!(None, ph.pop());
+    }
+
+    #[test]
+    fn drain_min_exact_size() {
+        let ph = (0..5).map(|n| (n, n)).collect::<PairingHeap<_, _>>();
+        let mut drain = ph.drain_min();
+        assert_eq!(5, drain.len());
+        assert_eq!((5, Some(5)), drain.size_hint());
+        assert_eq!(Some(0), drain.next());
+        assert_eq!(4, drain.len());
+        assert_eq!((4, Some(4)), drain.size_hint());
+        assert_eq!(4, drain.by_ref().count());
+        assert_eq!(0, drain.len());
+        assert_eq!(None, drain.next());
+        assert_eq!(None, drain.next());
+    }
+
+    #[test]
+    fn values_size_hint() {
+        let mut ph = (0..10).map(|n| (n, n)).collect::<PairingHeap<_, _>>();
+        ph.pop();
+        assert_eq!((9, Some(9)), ph.values().size_hint());
+        assert_eq!(ph.len(), ph.values().len());
+        assert_eq!((9, Some(9)), ph.values_mut().size_hint());
+        let mut values = ph.values();
+        values.next();
+        assert_eq!(8, values.len());
+    }
+
+    #[test]
+    fn iter() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..10).map(|n| ph.push(n, 10 - n)).collect::<Vec<_>>();
+        ph.remove(handles[3]);
+        let mut triples = ph.iter().collect::<Vec<_>>();
+        assert_eq!(9, triples.len());
+        triples.sort_by_key(|&(_, _, &elem)| elem);
+        for (handle, &key, &elem) in triples {
+            assert_eq!(handles[elem as usize], handle);
+            assert_eq!(10 - elem, key);
+            assert_eq!(Some(&elem), ph.get(handle));
+        }
+    }
+
+    #[test]
+    fn handles() {
+        let mut ph = (0..10).map(|n| (n, n)).collect::<PairingHeap<_, _>>();
+        ph.pop();
+        ph.pop();
+        let handles = ph.handles().collect::<Vec<_>>();
+        assert_eq!(ph.len(), handles.len());
+        assert!(handles.iter().all(|&handle| ph.contains(handle)));
+    }
+
+    #[test]
+    fn default() {
+        #[derive(Default)]
+        struct Queue {
+            heap: PairingHeap<char, i64>,
+        }
+        let mut queue = Queue::default();
+        assert!(queue.heap.is_empty());
+        assert_eq!(PairingStrategy::default(), queue.heap.strategy());
+        queue.heap.push('b', 2);
+        queue.heap.push('a', 1);
+        assert_eq!(Some('a'), queue.heap.pop());
+    }
+
+    #[test]
+    fn error_display() {
+        use std::error::Error as StdError;
+
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 1);
+        let err = ph.decrease_key(a, 2).unwrap_err();
+        assert_eq!(
+            "new key must be less than the current key for decrease_key",
+            err.to_string()
+        );
+        let boxed: Box<dyn StdError> = Box::new(err);
+        assert_eq!(err.to_string(), boxed.to_string());
+    }
+
+    #[test]
+    fn decrease_key_invalid_handle() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 1);
+        ph.push('b', 2);
+        assert_eq!(Some('a'), ph.pop());
+        assert_eq!(Err(Error::InvalidHandle), ph.decrease_key(a, 0));
+        assert_eq!(Err(Error::InvalidHandle), ph.change_key(a, 5));
+        assert_eq!(Some('b'), ph.pop());
+        assert_eq!(
+            Err(Error::InvalidHandle),
+            ph.decrease_key(Handle::from(42), 0)
+        );
+    }
+
+    #[cfg(feature = "generational")]
+    #[test]
+    fn generational_handles() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 1);
+        ph.push('b', 2);
+        assert_eq!(Some('a'), ph.pop());
+        let c = ph.push('c', 3);
+        assert_eq!(usize::from(a), usize::from(c));
+        assert_ne!(a, c);
+        assert!(!ph.contains(a));
+        assert_eq!(None, ph.get(a));
+        assert_eq!(None, ph.get_key(a));
+        assert_eq!(Err(Error::InvalidHandle), ph.decrease_key(a, 0));
+        assert_eq!(None, ph.remove(a));
+        assert_eq!(Some(&'c'), ph.get(c));
+        assert_eq!(Ok(()), ph.decrease_key(c, 0));
+        assert_eq!(Some('c'), ph.pop());
+        assert_eq!(Some('b'), ph.pop());
+    }
+
+    #[test]
+    fn string_keys() {
+        let mut ph = PairingHeap::new();
+        ph.push(1, String::from("pear"));
+        let apple = ph.push(2, String::from("apple"));
+        let fig = ph.push(3, String::from("fig"));
+        ph.push(4, String::from("banana"));
+        assert_eq!(Some(String::from("apple")), ph.peek_key());
+        assert_eq!(Ok(()), ph.decrease_key(fig, String::from("avocado")));
+        assert_eq!(Ok(()), ph.change_key(apple, String::from("cherry")));
+        assert_eq!(Some(String::from("cherry")), ph.get_key(apple));
+        assert_eq!(vec![3, 4, 2, 1], ph.into_sorted_vec());
+    }
+
+    #[test]
+    fn keys() {
+        let mut ph = (0..10).map(|n| (n, 10 * n)).collect::<PairingHeap<_, _>>();
+        ph.pop();
+        assert_eq!(ph.len(), ph.keys().count());
+        assert_eq!(Some(&90), ph.keys().max());
+        assert_eq!(450, ph.keys().sum::<i32>());
+    }
+
+    #[test]
+    fn update_or_push() {
+        let edges: &[&[(usize, u32)]] = &[
+            &[(1, 7), (2, 9), (5, 14)],
+            &[(0, 7), (2, 10), (3, 15)],
+            &[(0, 9), (1, 10), (3, 11), (5, 2)],
+            &[(1, 15), (2, 11), (4, 6)],
+            &[(3, 6), (5, 9)],
+            &[(0, 14), (2, 2), (4, 9)],
+        ];
+        let mut dist = vec![u32::MAX; edges.len()];
+        let mut handles = vec![None; edges.len()];
+        let mut ph = PairingHeap::new();
+        dist[0] = 0;
+        handles[0] = Some(ph.update_or_push(handles[0], 0, 0));
+        while let Some(d) = ph.peek_key() {
+            let u = ph.pop().unwrap();
+            for &(v, w) in edges[u] {
+                if d + w < dist[v] {
+                    dist[v] = d + w;
+                    handles[v] = Some(ph.update_or_push(handles[v], v, d + w));
+                }
+            }
+        }
+        assert_eq!(vec![0, 7, 9, 20, 20, 11], dist);
+        let a = ph.update_or_push(None, 1, 5);
+        assert_eq!(a, ph.update_or_push(Some(a), 2, 8));
+        assert_eq!(Some(&2), ph.get(a));
+        assert_eq!(Some(8), ph.get_key(a));
+    }
+
+    #[test]
+    fn replace() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 2);
+        let b = ph.push('b', 1);
+        let c = ph.push('c', 3);
+        assert_eq!(Some('a'), ph.replace(a, 'x'));
+        assert_eq!(Some('c'), ph.replace(c, 'y'));
+        assert_eq!(Some(1), ph.get_key(b));
+        assert_eq!(Some(2), ph.get_key(a));
+        assert_eq!(Some('b'), ph.pop());
+        assert_eq!(None, ph.replace(b, 'z'));
+        assert_eq!(vec!['x', 'y'], ph.into_sorted_vec());
+    }
+
+    #[test]
+    fn modify() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push(String::from("a"), 10);
+        let b = ph.push(String::from("b"), 20);
+        let c = ph.push(String::from("c"), 30);
+        let d = ph.push(String::from("d"), 40);
+        assert_eq!(
+            Ok(()),
+            ph.modify(c, |elem, key| {
+                elem.push('!');
+                *key = 5;
+            })
+        );
+        assert_eq!(Some(&String::from("c!")), ph.peek());
+        assert_eq!(Some(5), ph.get_key(c));
+        assert_eq!(Ok(()), ph.modify(c, |_, key| *key += 30));
+        assert_eq!(Ok(()), ph.modify(d, |elem, _| elem.make_ascii_uppercase()));
+        assert_eq!(Some(String::from("a")), ph.pop());
+        assert_eq!(
+            Err(Error::InvalidHandle),
+            ph.modify(a, |_, _| unreachable!())
+        );
+        assert_eq!(Some(&String::from("b")), ph.get(b));
+        assert_eq!(
+            vec![String::from("b"), String::from("c!"), String::from("D")],
+            ph.pop_n(3)
+        );
+    }
+
+    #[test]
+    fn set_key() {
+        use rand::{thread_rng, Rng};
+
+        let mut rng = thread_rng();
+        let mut ph = PairingHeap::new();
+        let mut keys = Vec::new();
+        let mut handles = Vec::new();
+        for elem in 0..100 {
+            let key = rng.gen_range(0, 1000);
+            keys.push(Some(key));
+            handles.push(ph.push(elem, key));
+        }
+        for _ in 0..1000 {
+            let elem = rng.gen_range(0, keys.len());
+            let key = rng.gen_range(0, 1000);
+            if keys[elem].is_some() {
+                assert_eq!(Ok(()), ph.set_key(handles[elem], key));
+                keys[elem] = Some(key);
+            } else {
+                assert_eq!(Err(Error::InvalidHandle), ph.set_key(handles[elem], key));
+            }
+            if !ph.is_empty() && rng.gen_range(0, 20) == 0 {
+                let key = ph.peek_key().unwrap();
+                let elem = ph.pop().unwrap();
+                assert_eq!(Some(key), keys[elem]);
+                assert!(keys.iter().flatten().all(|&other| other >= key));
+                keys[elem] = None;
+            }
+        }
+        let mut expected = keys.into_iter().flatten().collect::<Vec<_>>();
+        expected.sort();
+        let mut drained = Vec::new();
+        while let Some(key) = ph.peek_key() {
+            ph.pop();
+            drained.push(key);
+        }
+        assert_eq!(expected, drained);
+    }
+
+    #[test]
+    fn swap_values() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push('a', 1);
+        let b = ph.push('b', 2);
+        let c = ph.push('c', 3);
+        assert!(ph.swap_values(a, c));
+        assert!(ph.swap_values(b, b));
+        assert_eq!(Some(&'c'), ph.get(a));
+        assert_eq!(Some(&'a'), ph.get(c));
+        assert_eq!(Some(1), ph.get_key(a));
+        assert_eq!(Some(3), ph.get_key(c));
+        assert_eq!(Some('c'), ph.pop());
+        assert!(!ph.swap_values(a, b));
+        assert_eq!(vec!['b', 'a'], ph.into_sorted_vec());
+    }
+
+    #[test]
+    fn get_pair_mut() {
+        let mut ph = PairingHeap::new();
+        let a = ph.push(vec![1, 2], 1);
+        let b = ph.push(vec![3], 2);
+        {
+            let (a, b) = ph.get_pair_mut(a, b).unwrap();
+            a.append(b);
+            b.push(42);
+        }
+        assert_eq!(Some(&vec![1, 2, 3]), ph.get(a));
+        assert_eq!(Some(&vec![42]), ph.get(b));
+        assert_eq!(
+            Some((&mut vec![42], &mut vec![1, 2, 3])),
+            ph.get_pair_mut(b, a)
+        );
+        assert_eq!(None, ph.get_pair_mut(a, a));
+        ph.remove(b);
+        assert_eq!(None, ph.get_pair_mut(a, b));
+        assert_eq!(None, ph.get_pair_mut(b, a));
+    }
+
+    #[test]
+    fn clone_from() {
+        let mut source = PairingHeap::new();
+        let handles = (0..10).map(|key| source.push(key, key)).collect::<Vec<_>>();
+        let mut target = PairingHeap::new();
+        for key in 0..100 {
+            target.push(key, key);
+        }
+        let capacity = target.capacity();
+        target.clone_from(&source);
+        assert_eq!(capacity, target.capacity());
+        assert_eq!(source.len(), target.len());
+        for &handle in &handles {
+            assert_eq!(source.get(handle), target.get(handle));
+        }
+        source.remove(handles[3]);
+        source.remove(handles[7]);
+        target.clone_from(&source);
+        assert!(!target.contains(handles[3]));
+        assert_eq!(
+            usize::from(source.push(42, 42)),
+            usize::from(target.push(42, 42))
+        );
+        assert_eq!(source.clone().into_sorted_vec(), target.into_sorted_vec());
+    }
+
+    #[test]
+    fn clone_from_holes() {
+        let mut source = PairingHeap::new();
+        let handles = (0..10).map(|key| source.push(key, key)).collect::<Vec<_>>();
+        for &n in &[7, 2, 5] {
+            assert_eq!(Some(n), source.remove(handles[n]));
+        }
+        let mut cloned = source.clone();
+        let mut target = PairingHeap::new();
+        for key in 0..100 {
+            target.push(key, key);
+        }
+        target.clone_from(&source);
+        for key in 0..4 {
+            let expected = usize::from(source.push(key, key));
+            assert_eq!(expected, usize::from(cloned.push(key, key)));
+            assert_eq!(expected, usize::from(target.push(key, key)));
+        }
+        assert_eq!(source.clone().into_sorted_vec(), target.into_sorted_vec());
+    }
+
+    #[test]
+    fn multiset_eq() {
+        let entries = [('a', 3), ('b', 1), ('c', 3), ('d', 2), ('c', 3)];
+        let lhs = entries.iter().cloned().collect::<PairingHeap<_, _>>();
+        let mut rhs = entries.iter().rev().cloned().collect::<PairingHeap<_, _>>();
+        assert_eq!(lhs, rhs);
+        rhs.pop();
+        assert_ne!(lhs, rhs);
+        rhs.push('b', 1);
+        assert_eq!(lhs, rhs);
+        let other = [('a', 3), ('b', 1), ('a', 3), ('d', 2), ('c', 3)];
+        assert_ne!(lhs, other.iter().cloned().collect());
+        let other = [('a', 3), ('b', 1), ('c', 3), ('d', 2), ('c', 4)];
+        assert_ne!(lhs, other.iter().cloned().collect());
+        assert_eq!(PairingHeap::<char, i32>::new(), PairingHeap::new());
+    }
+
+    #[test]
+    fn from_vec() {
+        let keys = vec![5, 3, 9, 1, 1, 8, 0, 7, 2];
+        let ph = PairingHeap::from_vec(keys.iter().map(|&key| (key * 10, key)).collect());
+        assert_eq!(keys.len(), ph.len());
+        assert_eq!(Some(&0), ph.peek());
+        let mut expected = keys.iter().map(|&key| key * 10).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(expected, ph.into_sorted_vec());
+        assert!(PairingHeap::<(), i32>::from_vec(Vec::new()).is_empty());
+        let mut ph = PairingHeap::from_vec(vec![('a', 1)]);
+        ph.heapify();
+        assert_eq!(Some('a'), ph.pop());
+    }
+
+    #[test]
+    fn from_slice() {
+        let items: &[(char, i64)] = &[('c', 3), ('a', -1), ('d', 7), ('b', 0), ('e', 7)];
+        let ph = PairingHeap::from_slice(items);
+        assert_eq!(items.len(), ph.len());
+        assert_eq!(Some(&'a'), ph.peek());
+        let mut sorted = ph.into_sorted_vec();
+        sorted[3..].sort();
+        assert_eq!(vec!['a', 'b', 'c', 'd', 'e'], sorted);
+        assert!(PairingHeap::<char, i64>::from_slice(&[]).is_empty());
+    }
+
+    #[test]
+    fn push_bounded() {
+        use rand::{thread_rng, Rng};
+
+        let mut rng = thread_rng();
+        let keys = (0..1000)
+            .map(|_| rng.gen_range(0, 10_000))
+            .collect::<Vec<_>>();
+        let mut ph = PairingHeap::new();
+        let mut discarded = 0;
+        for &key in &keys {
+            if ph.push_bounded(key, key, 10).is_some() {
+                discarded += 1;
+            }
+        }
+        assert_eq!(990, discarded);
+        let mut expected = keys;
+        expected.sort();
+        expected.truncate(10);
+        assert_eq!(expected, ph.into_sorted_vec());
+        let mut ph = PairingHeap::new();
+        assert_eq!(Some('a'), ph.push_bounded('a', 1, 0));
+        assert!(ph.is_empty());
+    }
+
+    #[test]
+    fn push_bounded_stable() {
+        let mut ph = PairingHeap::new_stable();
+        let a = ph.push('a', 0);
+        ph.push('b', 1);
+        ph.push('c', 1);
+        assert_eq!(Some('a'), ph.remove(a));
+        // 'd' reuses the slot of 'a' but is still the most recently inserted of the ties.
+        assert_eq!(Some('d'), ph.push_bounded('d', 1, 2));
+        assert_eq!(Some('c'), ph.push_bounded('e', 0, 2));
+        assert_eq!(vec!['e', 'b'], ph.into_sorted_vec());
+    }
+
+    #[test]
+    fn iter_k_smallest() {
+        use rand::{thread_rng, Rng};
+
+        let mut rng = thread_rng();
+        let mut ph = PairingHeap::new();
+        let mut keys = Vec::new();
+        for _ in 0..1000 {
+            let key = rng.gen_range(0, 100_000);
+            keys.push(key);
+            ph.push(key, key);
+        }
+        ph.pop();
+        keys.sort();
+        keys.remove(0);
+        assert_eq!(
+            keys.iter().take(5).collect::<Vec<_>>(),
+            ph.iter_k_smallest(5).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            keys.iter().collect::<Vec<_>>(),
+            ph.iter_k_smallest(2000).collect::<Vec<_>>()
+        );
+        assert_eq!(999, ph.len());
+    }
+
+    #[test]
+    fn stable() {
+        let mut ph = PairingHeap::new_stable();
+        assert!(ph.is_stable());
+        assert!(!PairingHeap::<(), i32>::new().is_stable());
+        for elem in 0..20 {
+            ph.push(elem, elem % 3);
+        }
+        let c = ph.push(20, 5);
+        ph.pop();
+        assert_eq!(Ok(()), ph.decrease_key(c, 1));
+        assert_eq!(Some(13), ph.pops_until(c));
+        let mut drained = Vec::new();
+        while let Some(elem) = ph.pop() {
+            drained.push(elem);
+        }
+        assert_eq!(
+            vec![3, 6, 9, 12, 15, 18, 1, 4, 7, 10, 13, 16, 19, 20, 2, 5, 8, 11, 14, 17],
+            drained
+        );
+    }
+
+    #[test]
+    fn elements_stay_with_their_nodes() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..50).map(|n| ph.push(n * 2, n)).collect::<Vec<_>>();
+        for &handle in handles.iter().step_by(3) {
+            ph.remove(handle);
+        }
+        for _ in 0..5 {
+            ph.pop();
+        }
+        for value in ph.values_mut() {
+            *value += 1;
+        }
+        assert_eq!(ph.len(), ph.values().len());
+        for (handle, &key, &elem) in ph.iter() {
+            assert_eq!(key * 2 + 1, elem);
+            assert_eq!(Some(&elem), ph.get(handle));
+            assert_eq!(Some(key), ph.get_key(handle));
+        }
+        ph.assert_consistent();
+        let drained = ph.into_sorted_vec();
+        let mut sorted = drained.clone();
+        sorted.sort();
+        assert_eq!(sorted, drained);
+    }
+
+    #[test]
+    fn pairwise_union_without_allocation() {
+        use rand::{thread_rng, Rng};
+
+        fn pairwise_union_collected<T, K: Key>(ph: &mut PairingHeap<T, K>) {
+            if let Some(min) = ph.min {
+                let mut siblings = ph.siblings(min).collect::<Vec<_>>().into_iter();
+                while let Some(left) = siblings.next() {
+                    match siblings.next() {
+                        Some(right) => {
+                            ph.union(left, right);
+                        }
+                        None => ph.update_min(left),
+                    }
+                }
+            }
+        }
+
+        let mut rng = thread_rng();
+        let mut ph = PairingHeap::new();
+        for n in 0..500 {
+            ph.push(n, rng.gen_range(0, 50));
+            if rng.gen_range(0, 4) == 0 {
+                ph.pop();
+            }
+            let mut reference = ph.clone();
+            ph.pairwise_union();
+            pairwise_union_collected(&mut reference);
+            assert_eq!(reference.min, ph.min);
+            assert!(reference.nodes.iter().eq(ph.nodes.iter()));
+        }
+    }
+
+    #[test]
+    fn detached_siblings_form_singleton_rings() {
+        use rand::{thread_rng, Rng};
+
+        let mut rng = thread_rng();
+        let mut ph = PairingHeap::new();
+        let mut live = (0..1000)
+            .map(|n| {
+                let key = rng.gen_range(0, 100_000);
+                (ph.push(n, key), key)
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..2000 {
+            if rng.gen_range(0, 3) == 0 {
+                ph.pop();
+                live.retain(|&(handle, _)| ph.contains(handle));
+            } else if !live.is_empty() {
+                let pos = rng.gen_range(0, live.len());
+                let (handle, key) = live[pos];
+                let new_key = key - rng.gen_range(1, 1000);
+                assert_eq!(Ok(()), ph.decrease_key(handle, new_key));
+                live[pos].1 = new_key;
+            }
+            ph.assert_consistent();
+        }
+        let mut expected = live.iter().map(|&(_, key)| key).collect::<Vec<_>>();
+        expected.sort();
+        let mut drained = Vec::new();
+        while let Some(key) = ph.peek_key() {
+            ph.pop();
+            drained.push(key);
+        }
+        assert_eq!(expected, drained);
+    }
+
+    #[test]
+    fn memory_usage() {
+        let mut ph = PairingHeap::new();
+        let empty = ph.memory_usage();
+        for i in 0..1000 {
+            ph.push(i, i);
+        }
+        ph.pop();
+        let filled = ph.memory_usage();
+        assert!(filled > empty);
+        while ph.len() > 10 {
+            ph.pop();
+        }
+        ph.shrink_to_fit();
+        assert!(ph.memory_usage() < filled);
+        assert_eq!((990..1000).collect::<Vec<_>>(), ph.into_sorted_vec());
+    }
+
+    #[test]
+    fn root_count_and_max_depth() {
+        let mut ph = PairingHeap::with_strategy(PairingStrategy::TwoPass);
+        assert_eq!(0, ph.root_count());
+        assert_eq!(0, ph.max_depth());
+        for i in 0..8 {
+            ph.push(i, i);
+        }
+        assert_eq!(8, ph.root_count());
+        assert_eq!(0, ph.max_depth());
+        // Pairs up (1, 2), (3, 4), (5, 6) and 7 and then melds the winners from right to left
+        // which chains 1 -> 3 -> 5 -> 7.
+        assert_eq!(Some(0), ph.pop());
+        assert_eq!(1, ph.root_count());
+        assert_eq!(3, ph.max_depth());
+        ph.push(10, 10);
+        ph.push(11, 11);
+        assert_eq!(3, ph.root_count());
+        assert_eq!(3, ph.max_depth());
+        ph.clear();
+        assert_eq!(0, ph.root_count());
+        assert_eq!(0, ph.max_depth());
+    }
+
+    #[test]
+    fn check_consistency() {
+        use rand::{thread_rng, Rng};
+
+        let mut rng = thread_rng();
+        for &stable in &[false, true] {
+            let mut ph = if stable {
+                PairingHeap::new_stable()
+            } else {
+                PairingHeap::new()
+            };
+            let mut handles = Vec::new();
+            assert_eq!(Ok(()), ph.check_consistency());
+            for _ in 0..2000 {
+                let live = handles.len();
+                match rng.gen_range(0, 7) {
+                    0 | 1 => handles.push(ph.push((), rng.gen_range(0, 100))),
+                    2 => {
+                        ph.pop();
+                    }
+                    3 if live > 0 => {
+                        ph.remove(handles.swap_remove(rng.gen_range(0, live)));
+                    }
+                    4 if live > 0 => {
+                        let handle = handles[rng.gen_range(0, live)];
+                        ph.decrease_key(handle, rng.gen_range(0, 100)).ok();
+                    }
+                    5 if live > 0 => {
+                        let handle = handles[rng.gen_range(0, live)];
+                        ph.set_key(handle, rng.gen_range(0, 100)).unwrap();
+                    }
+                    6 => ph.consolidate(),
+                    _ => (),
+                }
+                handles.retain(|&handle| ph.contains(handle));
+                assert_eq!(Ok(()), ph.check_consistency());
+            }
+        }
+    }
+
+    #[test]
+    fn check_consistency_detects_violations() {
+        let mut ph = PairingHeap::with_strategy(PairingStrategy::TwoPass);
+        let handles = (0..4).map(|i| ph.push(i, i)).collect::<Vec<_>>();
+        ph.pop();
+        assert_eq!(Ok(()), ph.check_consistency());
+        ph.node_mut(handles[3]).key = -1;
+        assert!(ph.check_consistency().is_err());
+    }
+
+    #[test]
+    fn pretty_debug() {
+        let mut ph = PairingHeap::with_strategy(PairingStrategy::TwoPass);
+        for i in 0..8 {
+            ph.push(i, i);
+        }
+        assert_eq!(Some(0), ph.pop());
+        ph.push(8, 8);
+        assert!(format!("{:?}", ph).starts_with("PairingHeap { min: "));
+        let pretty = format!("{:#?}", ph);
+        let lines = pretty.lines().collect::<Vec<_>>();
+        assert_eq!(Some(&"PairingHeap {"), lines.first());
+        assert_eq!(Some(&"    1: 1"), lines.get(1));
+        assert_eq!(Some(&"}"), lines.last());
+        assert!(lines.contains(&"    8: 8"));
+        // Every element is indented one level deeper than its parent which has a lower key.
+        let entries = lines[1..lines.len() - 1]
+            .iter()
+            .map(|line| {
+                let key = line.trim_start();
+                (
+                    (line.len() - key.len()) / 4,
+                    key[..1].parse::<i32>().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        for (idx, &(depth, key)) in entries.iter().enumerate().filter(|e| (e.1).0 > 1) {
+            let &(parent_depth, parent_key) =
+                entries[..idx].iter().rev().find(|e| e.0 < depth).unwrap();
+            assert_eq!(depth - 1, parent_depth);
+            assert!(parent_key < key);
+        }
+        let deepest = entries.iter().map(|e| e.0).max();
+        assert_eq!(Some(ph.max_depth() + 1), deepest);
+        assert!(ph.max_depth() >= 2);
+        assert_eq!(ph.len() + 2, lines.len());
+        assert_eq!(
+            "PairingHeap {\n}",
+            format!("{:#?}", PairingHeap::<(), i32>::new())
+        );
+    }
+
+    #[test]
+    fn convert_from_vec_heap() {
+        let keys = (0..200).map(|n| (n * 73) % 211 - 100).collect::<Vec<i64>>();
+        let mut ph = PairingHeap::with_strategy(PairingStrategy::TwoPass);
+        for &key in &keys {
+            ph.push(key * 2, key);
+        }
+        ph.pop();
+        let expected = ph.clone().into_sorted_vec();
+        let converted = ::vec_heap::PairingHeap::from(ph);
+        assert_eq!(::vec_heap::PairingStrategy::TwoPass, converted.strategy());
+        assert_eq!(keys.len() - 1, converted.len());
+        let round_trip = PairingHeap::from(converted);
+        assert_eq!(PairingStrategy::TwoPass, round_trip.strategy());
+        assert_eq!(expected, round_trip.into_sorted_vec());
+
+        let mut ph = PairingHeap::with_comparator(|lhs: &i32, rhs| rhs.cmp(lhs));
+        ph.push('a', 1);
+        ph.push('b', 3);
+        ph.push('c', 2);
+        let converted = ::vec_heap::PairingHeap::from(ph);
+        assert_eq!(vec!['b', 'c', 'a'], converted.into_sorted_vec());
+
+        let mut ph = PairingHeap::new_stable();
+        for (idx, &key) in [2, 1, 2, 1, 2].iter().enumerate() {
+            ph.push(idx, key);
+        }
+        let converted = ::vec_heap::PairingHeap::from(ph);
+        assert!(converted.is_stable());
+        assert_eq!(
+            vec![1, 3, 0, 2, 4],
+            PairingHeap::from(converted).into_sorted_vec()
+        );
+    }
+
+    #[test]
+    fn split_off() {
+        let keys = (0..200).map(|n| (n * 73) % 211 - 100).collect::<Vec<i64>>();
+        let mut ph = keys.iter().map(|&k| (k, k)).collect::<PairingHeap<_, _>>();
+        ph.pop();
+        let upper = ph.split_off(10);
+        let lower = ph.into_sorted_vec();
+        let upper = upper.into_sorted_vec();
+        assert!(lower.iter().all(|&k| k < 10));
+        assert!(upper.iter().all(|&k| k >= 10));
+        assert!(upper.contains(&10));
+        let mut expected = keys.clone();
+        expected.sort();
+        expected.remove(0);
+        assert_eq!(expected, lower.into_iter().chain(upper).collect::<Vec<_>>());
+
+        let mut ph = PairingHeap::with_comparator(|lhs: &i32, rhs| rhs.cmp(lhs));
+        ph.extend(vec![('a', 1), ('b', 5), ('c', 3)]);
+        let upper = ph.split_off(3);
+        assert_eq!(vec!['b'], ph.into_sorted_vec());
+        assert_eq!(vec!['c', 'a'], upper.into_sorted_vec());
+    }
+
+    #[test]
+    fn peek_mut_key() {
+        let mut ph = PairingHeap::new();
+        assert!(!ph.peek_mut_key(0));
+        ph.push('a', 1);
+        ph.push('b', 2);
+        ph.push('c', 3);
+        ph.push('d', 4);
+        assert_eq!(Some('a'), ph.pop());
+        assert!(ph.peek_mut_key(10));
+        assert_eq!(Some(&'c'), ph.peek());
+        assert_eq!(
+            Some(10),
+            ph.get_key(ph.handles().find(|&h| ph[h] == 'b').unwrap())
+        );
+        assert!(ph.peek_mut_key(0));
+        assert_eq!(Some(&'c'), ph.peek());
+        assert_eq!(Some(0), ph.peek_key());
+        assert_eq!(Ok(()), ph.check_consistency());
+        assert_eq!(Some('c'), ph.pop());
+        assert_eq!(Some('d'), ph.pop());
+        assert_eq!(Some('b'), ph.pop());
+        assert_eq!(None, ph.pop());
+    }
+
+    #[test]
+    fn try_push() {
+        let mut ph = PairingHeap::with_max_len(3);
+        assert_eq!(Some(3), ph.max_len());
+        assert!(ph.capacity() >= 3);
+        let memory = ph.memory_usage();
+        let a = ph.try_push('a', 1).unwrap();
+        ph.try_push('b', 2).unwrap();
+        ph.try_push('c', 3).unwrap();
+        assert_eq!(Err(('d', 0)), ph.try_push('d', 0));
+        assert_eq!(3, ph.len());
+        assert_eq!(memory, ph.memory_usage());
+        assert_eq!(Some('a'), ph.remove(a));
+        assert!(ph.try_push('d', 0).is_ok());
+        assert_eq!(Err(('e', 5)), ph.try_push('e', 5));
+        assert_eq!(Some(3), ph.clone().max_len());
+        assert_eq!(vec!['d', 'b', 'c'], ph.into_sorted_vec());
+
+        let mut ph = PairingHeap::new();
+        assert_eq!(None, ph.max_len());
+        assert!((0..100).all(|i| ph.try_push(i, i).is_ok()));
+    }
+
+    #[test]
+    fn meld_all() {
+        use rand::{thread_rng, Rng};
+
+        let mut rng = thread_rng();
+        let mut expected = Vec::new();
+        let heaps = (0..10)
+            .map(|_| {
+                let mut ph = PairingHeap::new();
+                for _ in 0..rng.gen_range(0, 50) {
+                    let key = rng.gen_range(-100, 100);
+                    ph.push(key, key);
+                    expected.push(key);
+                }
+                if let Some(min) = ph.pop() {
+                    let idx = expected.iter().position(|&key| key == min).unwrap();
+                    expected.remove(idx);
+                }
+                ph
+            })
+            .collect::<Vec<_>>();
+        let melded = PairingHeap::meld_all(heaps);
+        assert_eq!(expected.len(), melded.len());
+        assert_eq!(Ok(()), melded.check_consistency());
+        expected.sort();
+        assert_eq!(expected, melded.into_sorted_vec());
+        assert!(PairingHeap::<(), i32>::meld_all(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn pop_n() {
+        let mut ph = PairingHeap::new();
+        for key in (0..20).rev() {
+            ph.push(key * 10, key);
+        }
+        assert_eq!(vec![0, 10, 20, 30, 40], ph.pop_n(5));
+        assert_eq!(15, ph.len());
+        assert_eq!(Some(&50), ph.peek());
+        assert_eq!(Vec::<i32>::new(), ph.pop_n(0));
+        assert_eq!(15, ph.pop_n(100).len());
+        assert!(ph.is_empty());
+        assert_eq!(Vec::<i32>::new(), ph.pop_n(3));
+    }
+
+    #[test]
+    fn pop_handle() {
+        let mut ph = PairingHeap::new();
+        assert_eq!(None, ph.pop_handle());
+        let a = ph.push('a', 2);
+        let b = ph.push('b', 1);
+        assert_eq!(Some((b, 'b')), ph.pop_handle());
+        assert!(!ph.contains(b));
+        assert!(ph.contains(a));
+        assert_eq!(Some((a, 'a')), ph.pop_handle());
+        assert!(!ph.contains(a));
+        assert_eq!(None, ph.pop_handle());
+    }
+
+    #[test]
+    fn values_with_keys() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..20)
+            .map(|n| ph.push(n, ((n * 7) % 20) as i32))
+            .collect::<Vec<_>>();
+        ph.pop();
+        assert_eq!(Ok(()), ph.decrease_key(handles[5], -1));
+        assert_eq!(ph.len(), ph.values_with_keys().count());
+        for (&elem, key) in ph.values_with_keys() {
+            assert_eq!(Some(key), ph.get_key(handles[elem]));
+        }
+        let mut pairs = ph.values_with_keys().collect::<Vec<_>>();
+        pairs.sort_by_key(|&(_, key)| key);
+        assert_eq!(
+            pairs.into_iter().map(|(&elem, _)| elem).collect::<Vec<_>>(),
+            ph.into_sorted_vec()
+        );
     }
 
-    /// Returns a reference to the current minimum element if not empty.
-    #[inline]
-    pub fn peek(&self) -> Option<&T> {
-        match self.min {
-            Some(min) => self.get(min),
-            None => None,
-        }
+    #[test]
+    fn clone_values() {
+        let mut ph = PairingHeap::new();
+        let handles = (0..10).map(|n| ph.push(n, n)).collect::<Vec<_>>();
+        ph.remove(handles[0]);
+        ph.remove(handles[4]);
+        let mut values = ph.values();
+        assert_eq!(Some(&1), values.next());
+        assert_eq!(Some(&2), values.next());
+        let rest = values.clone();
+        assert_eq!(6, rest.len());
+        assert_eq!(values.collect::<Vec<_>>(), rest.collect::<Vec<_>>());
+        assert_eq!(
+            vec![&3, &5, &6, &7, &8, &9],
+            ph.values().skip(2).collect::<Vec<_>>()
+        );
     }
 
-    /// Returns a reference to the current minimum element.
-    ///
-    /// Does not perform bounds checking so use it carefully!
-    ///
-    /// # Safety
-    ///
-    /// This is unsafe because the heap is not checked to be non-empty.
-    #[inline]
-    pub unsafe fn peek_unchecked(&self) -> &T {
-        match self.min {
-            Some(min) => self.get_unchecked(min),
-            None => ::unreachable::unreachable(),
+    #[test]
+    fn into_iter_ref() {
+        let mut ph = PairingHeap::new();
+        for i in 0..10 {
+            ph.push(i, 10 - i);
+        }
+        ph.pop();
+        let mut count = 0;
+        let mut sum = 0;
+        for &elem in &ph {
+            count += 1;
+            sum += elem;
         }
+        assert_eq!(9, count);
+        assert_eq!(36, sum);
+        assert_eq!(ph.len(), (&ph).into_iter().len());
     }
 
-    /// Returns a mutable reference to the current minimum element if not empty.
-    #[inline]
-    pub fn peek_mut(&mut self) -> Option<&mut T> {
-        match self.min {
-            Some(min) => self.get_mut(min),
-            None => None,
+    #[test]
+    fn iter_sorted() {
+        let mut ph = PairingHeap::new();
+        let mut handles = Vec::new();
+        for n in 0..200 {
+            let key = (n * 61) % 211;
+            handles.push(ph.push(n, key));
         }
+        for &handle in handles.iter().step_by(7) {
+            let key = ph.get_key(handle).unwrap();
+            ph.decrease_key(handle, key - 300).unwrap();
+        }
+        ph.pop();
+        let mut expected = ph
+            .iter()
+            .map(|(_, &key, &elem)| (key, elem))
+            .collect::<Vec<_>>();
+        expected.sort();
+        let expected = expected
+            .into_iter()
+            .map(|(_, elem)| elem)
+            .collect::<Vec<_>>();
+        let snapshot = ph.clone();
+        assert_eq!(expected, ph.iter_sorted().cloned().collect::<Vec<_>>());
+        assert_eq!(snapshot, ph);
+        assert_eq!(expected, ph.drain_min().collect::<Vec<_>>());
     }
 
-    /// Returns a reference to the current minimum element without bounds checking.
-    /// So use it very carefully!
-    ///
-    /// # Safety
-    ///
-    /// This is unsafe because the heap is not checked to be non-empty.
-    #[inline]
-    pub unsafe fn peek_unchecked_mut(&mut self) -> &mut T {
-        match self.min {
-            Some(min) => self.get_unchecked_mut(min),
-            None => ::unreachable::unreachable(),
+    #[test]
+    fn peek_second() {
+        let mut ph = PairingHeap::new();
+        assert_eq!(None, ph.peek_second());
+        ph.push(42, 42);
+        assert_eq!(None, ph.peek_second());
+        for n in 0..100 {
+            let key = (n * 53) % 101;
+            ph.push(key, key);
         }
+        assert_eq!(Some(0), ph.pop());
+        let snapshot = ph.clone();
+        assert_eq!(
+            snapshot.clone().drain_min().nth(1).as_ref(),
+            ph.peek_second()
+        );
+        assert_eq!(Some(&2), ph.peek_second());
+        assert!(snapshot == ph);
     }
 
-    /// Iterate over the values in this `PairingHeap` by reference in unspecified order.
-    #[inline]
-    pub fn values(&self) -> stash::Values<'_, T> {
-        self.elems.values()
+    #[test]
+    fn nth_smallest() {
+        let mut ph = PairingHeap::new();
+        for n in 0..101 {
+            let key = (n * 37) % 101;
+            ph.push(key, key);
+        }
+        assert_eq!(Some(0), ph.pop());
+        assert_eq!(ph.peek(), ph.nth_smallest(0));
+        assert_eq!(Some(&50), ph.nth_smallest(49));
+        assert_eq!(Some(&100), ph.nth_smallest(99));
+        assert_eq!(None, ph.nth_smallest(100));
+        assert_eq!(None, ph.nth_smallest(usize::MAX));
+        assert_eq!(100, ph.len());
+        assert_eq!(Some(1), ph.pop());
     }
 
-    /// Iterate over the values in this `PairingHeap` by mutable reference unspecified order.
-    #[inline]
-    pub fn values_mut(&mut self) -> stash::ValuesMut<'_, T> {
-        self.elems.values_mut()
+    #[test]
+    fn clear_and_free() {
+        let mut ph = PairingHeap::with_strategy(PairingStrategy::TwoPass);
+        for i in 0..10_000 {
+            ph.push(i, i);
+        }
+        ph.pop();
+        let capacity = ph.capacity();
+        ph.clear();
+        assert!(ph.is_empty());
+        assert_eq!(capacity, ph.capacity());
+        ph.clear_and_free();
+        assert!(ph.is_empty());
+        assert_eq!(0, ph.capacity());
+        assert_eq!(0, ph.memory_usage());
+        assert_eq!(PairingStrategy::TwoPass, ph.strategy());
+        ph.push(1, 1);
+        assert_eq!(Some(1), ph.pop());
     }
 
-    /// Iterate over values stored within a `PairingHeap` in a sorted-by-min order. Drains the heap.
-    #[inline]
-    pub fn drain_min(self) -> DrainMin<T, K> {
-        DrainMin { heap: self }
+    #[test]
+    fn option_handle_is_niche_optimized() {
+        use std::mem::size_of;
+
+        assert_eq!(size_of::<Handle>(), size_of::<Option<Handle>>());
+        #[cfg(not(feature = "generational"))]
+        assert_eq!(size_of::<usize>(), size_of::<Option<Handle>>());
+        assert_eq!(
+            size_of::<(Handle, Handle, Handle, Handle)>(),
+            size_of::<(Option<Handle>, Option<Handle>, Handle, Handle)>()
+        );
     }
-}
 
-use std::ops::{Index, IndexMut};
+    #[test]
+    fn u32_handles() {
+        #[cfg(not(feature = "generational"))]
+        {
+            use std::mem::size_of;
 
-impl<T, K> Index<Handle> for PairingHeap<T, K>
-where
-    K: Key,
-{
-    type Output = T;
+            assert_eq!(size_of::<u32>(), size_of::<Option<Handle<u32>>>());
+            assert!(size_of::<Node<u8, u8, u32>>() < size_of::<Node<u8, u8, usize>>());
+        }
 
-    fn index(&self, handle: Handle) -> &Self::Output {
-        self.elems
-            .get(handle)
-            .expect("no node found for given handle")
+        let count = 100_000;
+        let mut ph = PairingHeap::<usize, i64, u32>::default();
+        ph.set_strategy(PairingStrategy::TwoPass);
+        let handles = (0..count)
+            .map(|elem| ph.push(elem, elem as i64))
+            .collect::<Vec<Handle<u32>>>();
+        assert_eq!(count - 1, usize::from(handles[count - 1]));
+        for &handle in handles.iter().step_by(3) {
+            let elem = ph[handle];
+            assert_eq!(Ok(()), ph.decrease_key(handle, -(elem as i64) - 1));
+        }
+        assert!(ph.check_consistency().is_ok());
+        let mut expected = (0..count)
+            .map(|elem| {
+                (
+                    if elem % 3 == 0 {
+                        -(elem as i64) - 1
+                    } else {
+                        elem as i64
+                    },
+                    elem,
+                )
+            })
+            .collect::<Vec<_>>();
+        expected.sort();
+        let popped = ph.drain_min().collect::<Vec<_>>();
+        assert_eq!(
+            expected
+                .into_iter()
+                .map(|(_, elem)| elem)
+                .collect::<Vec<_>>(),
+            popped
+        );
     }
-}
 
-impl<T, K> IndexMut<Handle> for PairingHeap<T, K>
-where
-    K: Key,
-{
-    fn index_mut(&mut self, handle: Handle) -> &mut Self::Output {
-        self.elems
-            .get_mut(handle)
-            .expect("no node found for given handle")
+    #[test]
+    fn u32_handles_configured() {
+        let mut ph = PairingHeap::<char, i32, u32>::default();
+        ph.set_comparator(|lhs: &i32, rhs: &i32| rhs.cmp(lhs));
+        ph.set_stable(true);
+        ph.set_max_len(Some(4));
+        assert!(ph.is_stable());
+        assert_eq!(Some(4), ph.max_len());
+        for &(elem, key) in &[('a', 1), ('b', 2), ('c', 2), ('d', 0)] {
+            assert!(ph.try_push(elem, key).is_ok());
+        }
+        assert_eq!(Err(('e', 3)), ph.try_push('e', 3));
+        assert_eq!(vec!['b', 'c', 'a', 'd'], ph.drain_min().collect::<Vec<_>>());
     }
-}
 
-/// Iterator over values stored within a `PairingHeap` in a sorted-by-min order. Drains the heap.
-pub struct DrainMin<T, K: Key> {
-    heap: PairingHeap<T, K>,
-}
+    #[test]
+    #[should_panic(expected = "cannot change the comparator of a non-empty heap")]
+    fn set_comparator_non_empty() {
+        let mut ph = PairingHeap::<char, i32, u32>::default();
+        ph.push('a', 1);
+        ph.set_comparator(|lhs: &i32, rhs: &i32| rhs.cmp(lhs));
+    }
 
-impl<T, K: Key> Iterator for DrainMin<T, K> {
-    type Item = T;
+    #[test]
+    fn u16_handles_try_push() {
+        let mut ph = PairingHeap::<(), u16, u16>::default();
+        for key in 0..u16::MAX {
+            assert!(ph.try_push((), key).is_ok());
+        }
+        assert_eq!(Err(((), 0)), ph.try_push((), 0));
+        let min = ph.peek_handle().unwrap();
+        ph.remove(min);
+        assert!(ph.try_push((), 0).is_ok());
+        assert_eq!(Err(((), 1)), ph.try_push((), 1));
+        assert_eq!(usize::from(u16::MAX), ph.len());
+    }
 
-    #[inline]
-    fn next(&mut self) -> Option<Self::Item> {
-        self.heap.pop()
+    #[test]
+    #[should_panic(expected = "handle index out of range")]
+    fn u16_handles_overflow() {
+        let mut ph = PairingHeap::<(), u16, u16>::default();
+        for key in 0..=u16::MAX {
+            ph.push((), key);
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_from_vec() {
+        let items = (0..10_000)
+            .map(|n| (n, (n * 7919) % 10_007))
+            .collect::<Vec<_>>();
+        let expected = PairingHeap::from_vec(items.clone())
+            .drain_min()
+            .collect::<Vec<_>>();
+        for &threads in &[1, 2, 3, 8] {
+            let ph = PairingHeap::par_from_vec_in(items.clone(), threads);
+            assert_eq!(items.len(), ph.len());
+            assert_eq!(expected, ph.drain_min().collect::<Vec<_>>());
+        }
+        let ph = PairingHeap::par_from_vec(items);
+        assert_eq!(expected, ph.drain_min().collect::<Vec<_>>());
+        let small = PairingHeap::par_from_vec_in(vec![('a', 2), ('b', 1)], 4);
+        assert_eq!(vec!['b', 'a'], small.drain_min().collect::<Vec<_>>());
+        assert!(PairingHeap::<(), i32>::par_from_vec(Vec::new()).is_empty());
+    }
 
     #[test]
-    fn take_min() {
-        let mut ph = PairingHeap::new();
-        ph.push(0, 6);
-        ph.push(1, 10);
-        ph.push(2, -42);
-        ph.push(3, 1337);
-        ph.push(4, -1);
-        ph.push(5, 1);
-        ph.push(6, 2);
-        ph.push(7, 3);
-        ph.push(8, 4);
-        ph.push(9, 5);
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(2), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(4), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(5), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(6), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(7), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(8), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(9), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(0), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(1), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(Some(3), ph.pop());
-        println!("ph = {:?}", ph);
-        assert_eq!(None, ph.pop());
-        println!("ph = {:?}", ph);
+    fn send_sync() {
+        use std::sync::{Arc, Mutex};
+
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<PairingHeap<i64, i64>>();
+        assert_send_sync::<Handle>();
+        assert_send_sync::<Values<'static, i64, i64>>();
+        assert_send_sync::<PairingHeap<i64, i64, u32>>();
+        assert_send_sync::<Remapping>();
+
+        let shared = Arc::new(Mutex::new(PairingHeap::new()));
+        let workers = (0..4)
+            .map(|n| {
+                let shared = Arc::clone(&shared);
+                ::std::thread::spawn(move || {
+                    shared.lock().unwrap().push(n, n as i64);
+                })
+            })
+            .collect::<Vec<_>>();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        let ph = Arc::try_unwrap(shared).unwrap().into_inner().unwrap();
+        assert_eq!(vec![0, 1, 2, 3], ph.into_sorted_vec());
     }
 
     #[test]
-    fn decrease_key() {
-        let mut ph = PairingHeap::new();
-        let a = ph.push(0, 0);
-        let b = ph.push(1, 50);
-        let c = ph.push(2, 100);
-        let d = ph.push(3, 150);
-        let e = ph.push(4, 200);
-        let f = ph.push(5, 250);
-        assert_eq!(Some(&0), ph.peek());
-        assert_eq!(Ok(()), ph.decrease_key(f, -50));
-        assert_eq!(Some(&5), ph.peek());
-        assert_eq!(Ok(()), ph.decrease_key(e, -100));
-        assert_eq!(Some(&4), ph.peek());
-        assert_eq!(Ok(()), ph.decrease_key(d, -99));
-        assert_eq!(Some(&4), ph.peek());
-        assert_eq!(Err(Error::DecreaseKeyOutOfOrder), ph.decrease_key(c, 1000));
-        assert_eq!(Some(&4), ph.peek());
-        assert_eq!(Ok(()), ph.decrease_key(b, -1000));
-        assert_eq!(Some(&1), ph.peek());
-        assert_eq!(Err(Error::DecreaseKeyOutOfOrder), ph.decrease_key(a, 100));
-        assert_eq!(Some(&1), ph.peek());
+    fn merge_drain() {
+        let mut heaps = vec![PairingHeap::new(), PairingHeap::new(), PairingHeap::new()];
+        let mut expected = Vec::new();
+        for n in 0..90 {
+            let key = (n * 37) % 91;
+            heaps[n % 3].push(key, key);
+            expected.push(key);
+        }
+        heaps[1].push(30, 30);
+        expected.push(30);
+        heaps.push(PairingHeap::new());
+        expected.sort();
+        assert_eq!(expected, super::merge_drain(heaps).collect::<Vec<_>>());
+
+        let mut fst = PairingHeap::new();
+        let mut snd = PairingHeap::new();
+        snd.push('c', 1);
+        fst.push('a', 1);
+        snd.push('b', 0);
+        assert_eq!(
+            vec!['b', 'a', 'c'],
+            super::merge_drain(vec![fst, snd]).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            0,
+            super::merge_drain(Vec::<PairingHeap<(), i32>>::new()).count()
+        );
+    }
+
+    #[test]
+    fn iter_sorted_stable() {
+        let mut ph = PairingHeap::new_stable();
+        for (elem, key) in "abcdefgh".chars().zip(&[2, 1, 2, 1, 2, 1, 0, 2]) {
+            ph.push(elem, *key);
+        }
+        ph.pop();
+        ph.push('i', 1);
+        let sorted = ph.iter_sorted().cloned().collect::<Vec<_>>();
+        assert_eq!(vec!['b', 'd', 'f', 'i', 'a', 'c', 'e', 'h'], sorted);
+        assert_eq!(Some(&'d'), ph.peek_second());
+        assert_eq!(Some(&'a'), ph.nth_smallest(4));
+        assert_eq!(sorted, ph.into_sorted_vec());
     }
 
     #[test]
@@ -843,6 +4957,54 @@ mod tests {
         // cannot test order of values since it is unspecified!
         assert_eq!(values.count(), 18);
     }
+
+    #[test]
+    fn set_strategy() {
+        let mut ph = setup();
+        assert_eq!(ph.strategy(), PairingStrategy::SinglePass);
+        assert_eq!(ph.pop(), Some('m'));
+        assert_eq!(ph.pop(), Some('j'));
+        ph.set_strategy(PairingStrategy::MultiPass);
+        assert_eq!(ph.strategy(), PairingStrategy::MultiPass);
+        ph.push('x', 44);
+        ph.push('x', 44);
+        assert_eq!(ph.pop(), Some('k'));
+        assert_eq!(ph.pop(), Some('d'));
+        ph.set_strategy(PairingStrategy::SinglePass);
+        ph.push('y', -4);
+        assert_eq!(ph.drain_min().collect::<String>(), "syqonprifgxxbalce");
+    }
+
+    #[test]
+    fn dump_sorted() {
+        let ph = setup();
+        let expected = "-123: m\n-100: j\n-77: k\n-25: d\n-5: s\n-3: q\n-1: o\n0: n\n2: p\n\
+                        4: r\n41: i\n42: f\n43: g\n50: b\n100: a\n123: l\n150: c\n999: e\n";
+        assert_eq!(ph.dump_sorted(), expected);
+    }
+
+    #[test]
+    fn to_dot() {
+        let ph = setup();
+        let dot = ph.to_dot();
+        assert!(dot.starts_with("digraph PairingHeap {\n"));
+        assert!(dot.ends_with("}\n"));
+        let nodes = dot.lines().filter(|line| line.contains("[label=")).count();
+        let edges = dot.lines().filter(|line| line.contains(" -> ")).count();
+        assert_eq!(ph.len(), nodes);
+        assert_eq!(ph.len() - ph.root_count(), edges);
+        assert_eq!(
+            1,
+            dot.lines()
+                .filter(|line| line.contains("style=bold"))
+                .count()
+        );
+        assert!(dot.contains("[label=\"-123 (#"));
+        assert_eq!(
+            "digraph PairingHeap {\n}\n",
+            PairingHeap::<(), i32>::new().to_dot()
+        );
+    }
 }
 
 #[cfg(all(feature = "bench", test))]
@@ -939,6 +5101,29 @@ mod bench {
         });
     }
 
+    #[bench]
+    fn ptr_pairing_heap_from_vec(bencher: &mut Bencher) {
+        let sample = setup_sample();
+        bencher.iter(|| {
+            let items = sample.iter().map(|&key| ((), key)).collect::<Vec<_>>();
+            let mut ph = PairingHeap::from_vec(items);
+            black_box(ph.pop());
+        });
+    }
+
+    #[bench]
+    fn ptr_pairing_heap_push_collected(bencher: &mut Bencher) {
+        let sample = setup_sample();
+        bencher.iter(|| {
+            let items = sample.iter().map(|&key| ((), key)).collect::<Vec<_>>();
+            let mut ph = PairingHeap::new();
+            for (elem, key) in items {
+                ph.push(elem, key);
+            }
+            black_box(ph.pop());
+        });
+    }
+
     #[bench]
     fn ptr_pairing_heap_pop(bencher: &mut Bencher) {
         let mut ph = PairingHeap::new();
@@ -951,6 +5136,48 @@ mod bench {
         });
     }
 
+    fn bench_pop_with_strategy(bencher: &mut Bencher, strategy: PairingStrategy) {
+        let mut ph = PairingHeap::with_strategy(strategy);
+        for key in setup_sample().into_iter() {
+            ph.push((), key);
+        }
+        bencher.iter(|| {
+            let mut ph = ph.clone();
+            while let Some(_) = black_box(ph.pop()) {}
+        });
+    }
+
+    #[bench]
+    fn ptr_pairing_heap_pop_single_pass(bencher: &mut Bencher) {
+        bench_pop_with_strategy(bencher, PairingStrategy::SinglePass)
+    }
+
+    #[bench]
+    fn ptr_pairing_heap_pop_two_pass(bencher: &mut Bencher) {
+        bench_pop_with_strategy(bencher, PairingStrategy::TwoPass)
+    }
+
+    #[bench]
+    fn ptr_pairing_heap_pop_multi_pass(bencher: &mut Bencher) {
+        bench_pop_with_strategy(bencher, PairingStrategy::MultiPass)
+    }
+
+    #[bench]
+    fn ptr_pairing_heap_pop_small_elems(bencher: &mut Bencher) {
+        let mut ph = PairingHeap::new();
+        for key in setup_sample().into_iter() {
+            ph.push(key as u64, key);
+        }
+        bencher.iter(|| {
+            let mut ph = ph.clone();
+            let mut sum = 0u64;
+            while let Some(elem) = ph.pop() {
+                sum = sum.wrapping_add(elem);
+            }
+            black_box(sum)
+        });
+    }
+
     #[bench]
     fn ptr_pairing_heap_pop_bigpod(bencher: &mut Bencher) {
         let mut ph = PairingHeap::new();
@@ -999,6 +5226,19 @@ mod bench {
         });
     }
 
+    #[bench]
+    fn ptr_pairing_heap_clone_from(bencher: &mut Bencher) {
+        let mut ph = PairingHeap::new();
+        for key in setup_sample().into_iter() {
+            ph.push((), key);
+        }
+        let mut target = ph.clone();
+        bencher.iter(|| {
+            target.clone_from(&ph);
+            black_box(&target);
+        });
+    }
+
     #[bench]
     fn binary_heap_clone(bencher: &mut Bencher) {
         let mut bh = BinaryHeap::new();
@@ -1009,4 +5249,32 @@ mod bench {
             black_box(&bh.clone());
         });
     }
+
+    fn setup_heap(n: i64) -> PairingHeap<(), i64> {
+        let mut ph = PairingHeap::new();
+        for key in 0..n {
+            ph.push((), key);
+        }
+        ph
+    }
+
+    #[bench]
+    fn ptr_pairing_heap_meld(bencher: &mut Bencher) {
+        let large = setup_heap(1_000_000);
+        let small = setup_heap(10_000);
+        bencher.iter(|| {
+            let mut large = large.clone();
+            black_box(large.meld(small.clone()));
+        });
+    }
+
+    #[bench]
+    fn ptr_pairing_heap_meld_naive(bencher: &mut Bencher) {
+        let large = setup_heap(1_000_000);
+        let small = setup_heap(10_000);
+        bencher.iter(|| {
+            let mut small = small.clone();
+            black_box(small.absorb(large.clone()));
+        });
+    }
 }
@@ -0,0 +1,247 @@
+//! A minimal self-describing data format used to round-trip heaps through serde in tests.
+//!
+//! Only integers and sequences are supported which is all the heap representations need.
+
+use serde::de::value::{Error, SeqDeserializer};
+use serde::de::{self, Deserialize, IntoDeserializer, Visitor};
+use serde::ser::{self, Error as SerError, Impossible, Serialize};
+
+/// A serialized value of the test data format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Int(i64),
+    Seq(Vec<Value>),
+}
+
+/// Serializes `value` into the test data format and deserializes it back.
+pub fn round_trip<S, D>(value: &S) -> D
+where
+    S: Serialize,
+    D: for<'de> Deserialize<'de>,
+{
+    let serialized = value.serialize(Serializer).expect("failed to serialize");
+    D::deserialize(serialized).expect("failed to deserialize")
+}
+
+/// Deserializes a sequence of the given values that announces `len` elements up front.
+pub fn from_seq_with_len<D>(values: Vec<Value>, len: usize) -> D
+where
+    D: for<'de> Deserialize<'de>,
+{
+    let values = Announced {
+        values: values.into_iter(),
+        len,
+    };
+    D::deserialize(SeqDeserializer::<_, Error>::new(values)).expect("failed to deserialize")
+}
+
+/// Yields values while reporting an exact length that may differ from their actual number.
+struct Announced {
+    values: ::std::vec::IntoIter<Value>,
+    len: usize,
+}
+
+impl Iterator for Announced {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        self.values.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+/// Serializer into the test data format.
+pub struct Serializer;
+
+/// Collects the elements of serialized sequences and tuples.
+pub struct SeqSerializer(Vec<Value>);
+
+fn unsupported<T>() -> Result<T, Error> {
+    Err(Error::custom("unsupported by the test data format"))
+}
+
+impl ser::Serializer for Serializer {
+    type Ok = Value;
+    type Error = Error;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = Impossible<Value, Error>;
+    type SerializeTupleVariant = Impossible<Value, Error>;
+    type SerializeMap = Impossible<Value, Error>;
+    type SerializeStruct = Impossible<Value, Error>;
+    type SerializeStructVariant = Impossible<Value, Error>;
+
+    fn serialize_bool(self, _: bool) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_i8(self, v: i8) -> Result<Value, Error> {
+        self.serialize_i64(i64::from(v))
+    }
+    fn serialize_i16(self, v: i16) -> Result<Value, Error> {
+        self.serialize_i64(i64::from(v))
+    }
+    fn serialize_i32(self, v: i32) -> Result<Value, Error> {
+        self.serialize_i64(i64::from(v))
+    }
+    fn serialize_i64(self, v: i64) -> Result<Value, Error> {
+        Ok(Value::Int(v))
+    }
+    fn serialize_u8(self, v: u8) -> Result<Value, Error> {
+        self.serialize_i64(i64::from(v))
+    }
+    fn serialize_u16(self, v: u16) -> Result<Value, Error> {
+        self.serialize_i64(i64::from(v))
+    }
+    fn serialize_u32(self, v: u32) -> Result<Value, Error> {
+        self.serialize_i64(i64::from(v))
+    }
+    fn serialize_u64(self, _: u64) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_f32(self, _: f32) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_f64(self, _: f64) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_char(self, _: char) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_str(self, _: &str) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_bytes(self, _: &[u8]) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_none(self) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_some<T: ?Sized + Serialize>(self, _: &T) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_unit(self) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Value, Error> {
+        unsupported()
+    }
+    fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer, Error> {
+        Ok(SeqSerializer(Vec::with_capacity(len.unwrap_or(0))))
+    }
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer, Error> {
+        self.serialize_seq(Some(len))
+    }
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, Error> {
+        unsupported()
+    }
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, Error> {
+        unsupported()
+    }
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+        unsupported()
+    }
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, Error> {
+        unsupported()
+    }
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, Error> {
+        unsupported()
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        self.0.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        Ok(Value::Seq(self.0))
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = Error;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Int(v) => visitor.visit_i64(v),
+            Value::Seq(values) => visitor.visit_seq(SeqDeserializer::new(values.into_iter())),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Value;
+
+    fn into_deserializer(self) -> Value {
+        self
+    }
+}